@@ -0,0 +1,161 @@
+// adaptive difficulty, moving the generator settings up and down so the player gets a target
+// share of the questions right
+
+use std::{collections::VecDeque, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::equation::config::{GeneratorConfig, OperatorWeights};
+
+/// The hardest level questions can get to
+const MAX_LEVEL: f32 = 10.0;
+
+/// The number of recent answers the accuracy is worked out from
+const RECENT_ANSWERS: usize = 10;
+
+/// How far the level moves after an answer when the recent accuracy is as far from the target
+/// as it can be
+const LEVEL_STEP: f32 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdaptiveDifficulty {
+    /// How hard the questions are, from 0 to [`MAX_LEVEL`]
+    level: f32,
+    target_success_rate: f32,
+    /// Whether each recent answer was right and how long it took, oldest first
+    recent_answers: VecDeque<(bool, Duration)>,
+}
+
+impl AdaptiveDifficulty {
+    pub fn new(target_success_rate: f32) -> Self {
+        Self {
+            level: 2.0,
+            target_success_rate: target_success_rate.clamp(0.05, 0.95),
+            recent_answers: VecDeque::with_capacity(RECENT_ANSWERS),
+        }
+    }
+
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// The share of the recent answers which were right, `None` before anything's been answered
+    pub fn recent_success_rate(&self) -> Option<f32> {
+        if self.recent_answers.is_empty() {
+            return None;
+        }
+        let successes = self
+            .recent_answers
+            .iter()
+            .filter(|(correct, _)| *correct)
+            .count();
+        Some(successes as f32 / self.recent_answers.len() as f32)
+    }
+
+    fn recent_average_time(&self) -> Duration {
+        let total: Duration = self.recent_answers.iter().map(|(_, time)| *time).sum();
+        total / self.recent_answers.len().max(1) as u32
+    }
+
+    /// How long a question at the current level should take to answer
+    fn expected_time(&self) -> Duration {
+        Duration::from_secs_f32(5.0 + 2.0 * self.level)
+    }
+
+    /// Moves the level towards where the recent accuracy matches the target, going up more
+    /// slowly if the answers are taking longer than expected
+    pub fn record_answer(&mut self, correct: bool, time_taken: Duration) {
+        if self.recent_answers.len() == RECENT_ANSWERS {
+            self.recent_answers.pop_front();
+        }
+        self.recent_answers.push_back((correct, time_taken));
+
+        let success_rate = self
+            .recent_success_rate()
+            .unwrap_or(self.target_success_rate);
+        let change = if success_rate >= self.target_success_rate {
+            let speed = if self.recent_average_time() <= self.expected_time() {
+                1.0
+            } else {
+                0.5
+            };
+            LEVEL_STEP * speed * (success_rate - self.target_success_rate)
+                / (1.0 - self.target_success_rate)
+        } else {
+            LEVEL_STEP * (success_rate - self.target_success_rate) / self.target_success_rate
+        };
+        self.level = (self.level + change).clamp(0.0, MAX_LEVEL);
+    }
+
+    /// The generator settings for the current level
+    pub fn generator_config(&self) -> GeneratorConfig {
+        let level = self.level;
+        let max_operand = 10 + (level * 9.0).round() as i64;
+        GeneratorConfig {
+            min_depth: 0,
+            max_depth: match level {
+                level if level < 3.0 => 0,
+                level if level < 7.0 => 1,
+                _ => 2,
+            },
+            min_operand: -max_operand,
+            max_operand,
+            decimal_places: match level {
+                level if level < 4.0 => 0,
+                level if level < 8.0 => 1,
+                _ => 2,
+            },
+            // multiplying and dividing come in as the level goes up
+            operator_weights: OperatorWeights {
+                add: 4,
+                subtract: 4,
+                multiply: 1 + (level / 2.0) as u32,
+                divide: (level / 3.0) as u32,
+            },
+            single_operator_chance: f64::from(level / MAX_LEVEL) * 0.25,
+            allow_negatives: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::equation::{Evaluate, medium::new_configured_equation};
+
+    #[test]
+    fn settles_at_target_success_rate() {
+        let mut adaptive = AdaptiveDifficulty::new(0.8);
+        for _ in 0..20 {
+            adaptive.record_answer(true, Duration::from_secs(1));
+        }
+        assert_eq!(adaptive.level(), MAX_LEVEL);
+
+        // four in five right should hold the level steady
+        let level = adaptive.level();
+        for index in 0..20 {
+            adaptive.record_answer(index % 5 != 0, Duration::from_secs(1));
+        }
+        assert!((adaptive.level() - level).abs() <= LEVEL_STEP);
+
+        for _ in 0..40 {
+            adaptive.record_answer(false, Duration::from_secs(1));
+        }
+        assert_eq!(adaptive.level(), 0.0);
+        assert_eq!(adaptive.generator_config().decimal_places, 0);
+    }
+
+    #[test]
+    fn hardest_level_generates_equations() {
+        let mut adaptive = AdaptiveDifficulty::new(0.8);
+        adaptive.level = MAX_LEVEL;
+        let config = adaptive.generator_config();
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..200 {
+            let equation = new_configured_equation(&mut rng, &config);
+            assert!(equation.calc_value().decimal_places().is_some());
+        }
+    }
+}
@@ -0,0 +1,308 @@
+// running from the command line without opening the window, e.g. `iced worksheet --questions 20`
+// or `iced play` to answer questions in the terminal
+
+use std::{
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+
+use wasm_timer::Instant;
+
+use crate::{
+    EquationDifficulty, GameMode, MainMenuState, Message, Model, ProgramState, QuestionRecord,
+    SessionSettings, history::History, review::ReviewDeck, update, worksheet::Worksheet,
+};
+
+const USAGE: &str = "Usage:
+  iced                        Open the game
+  iced play [options]         Answer questions in the terminal, `iced --tui` does the same
+  iced worksheet [options]    Save a worksheet as html and pdf
+
+Options:
+  --difficulty <name>  simple, medium, complex, adaptive, custom or a practice set like
+                       times-tables-2-12 (default simple)
+  --mode <name>        standard, sprint or review-mistakes, only when playing
+                       (default standard)
+  --questions <number> How many questions, at least 1 (default 10 to play, 20 on a
+                       worksheet)
+  --seed <number>      Generates the same questions as another time (default random)
+  --columns <number>   How many columns a worksheet has, at most 4 (default 2)
+  --output <path>      Where to save a worksheet, .html and .pdf are added (default worksheet)
+
+Type quit while playing to end early.";
+
+/// Typed instead of an answer to end the session early
+const QUIT_COMMAND: &str = "quit";
+
+/// Runs the command in `args`, without the program name, returning the exit code
+pub fn run(args: &[String]) -> i32 {
+    let result = match args.first().map(String::as_str) {
+        Some("play" | "--tui") => run_play(&args[1..], io::stdin().lock(), io::stdout().lock()),
+        Some("worksheet") => run_worksheet(&args[1..]),
+        Some("help" | "--help" | "-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(format!("Unknown command\n\n{}", USAGE)),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("{}", err);
+            1
+        }
+    }
+}
+
+/// The options after a command, read as `--name value` pairs
+#[derive(Debug, Default)]
+struct Options {
+    settings: SessionSettings,
+    game_mode: Option<GameMode>,
+    questions: Option<usize>,
+    columns: Option<usize>,
+    seed: Option<u64>,
+    output: Option<PathBuf>,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(name) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", name))?;
+        let number_error = |_| format!("{} needs a number, not '{}'", name, value);
+        match name.as_str() {
+            "--difficulty" => {
+                options.settings.equation_difficulty = parse_difficulty(value)?;
+            }
+            "--mode" => options.game_mode = Some(parse_game_mode(value)?),
+            "--questions" => {
+                let questions = value.parse().map_err(number_error)?;
+                if questions == 0 {
+                    return Err(format!("--questions needs to be at least 1\n\n{}", USAGE));
+                }
+                options.questions = Some(questions);
+            }
+            "--columns" => options.columns = Some(value.parse().map_err(number_error)?),
+            "--seed" => options.seed = Some(value.parse().map_err(number_error)?),
+            "--output" => options.output = Some(PathBuf::from(value)),
+            _ => return Err(format!("Unknown option {}\n\n{}", name, USAGE)),
+        }
+    }
+    Ok(options)
+}
+
+/// Finds a difficulty by its name in lowercase with dashes for spaces, e.g. `times-tables-2-12`
+fn parse_difficulty(name: &str) -> Result<EquationDifficulty, String> {
+    let slug = |text: &str| text.to_lowercase().replace(' ', "-");
+    EquationDifficulty::all()
+        .find(|difficulty| slug(&difficulty.to_string()) == slug(name))
+        .ok_or_else(|| format!("Unknown difficulty '{}'", name))
+}
+
+fn parse_game_mode(name: &str) -> Result<GameMode, String> {
+    let slug = |text: &str| text.to_lowercase().replace(' ', "-");
+    [GameMode::Standard, GameMode::Sprint, GameMode::Review]
+        .into_iter()
+        .find(|game_mode| slug(&game_mode.to_string()) == slug(name))
+        .ok_or_else(|| format!("Unknown game mode '{}'", name))
+}
+
+/// Plays a session by sending the same messages the window would, reading answers from
+/// `input` a line at a time
+fn run_play(
+    args: &[String],
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), String> {
+    let options = parse_options(args)?;
+    if options.columns.is_some() || options.output.is_some() {
+        return Err(
+            "--columns and --output only work on a worksheet, not when playing".to_string(),
+        );
+    }
+    let mut settings = options.settings;
+    if let Some(game_mode) = options.game_mode {
+        settings.game_mode = game_mode;
+    }
+    if let Some(questions) = options.questions {
+        settings.number_equations = u32::try_from(questions)
+            .map_err(|_| format!("--questions can be at most {}", u32::MAX))?;
+    }
+    let mut model = Model {
+        program_state: ProgramState::MainMenu(MainMenuState {
+            seed_text: options
+                .seed
+                .map(|seed| seed.to_string())
+                .unwrap_or_default(),
+            ..Default::default()
+        }),
+        settings,
+        history: History::load(),
+        review_deck: ReviewDeck::load(),
+        ..Default::default()
+    };
+    // the timer only starts counting from the second tick
+    let _ = update(&mut model, Message::Tick(Instant::now()));
+    let _ = update(&mut model, Message::StartGame);
+
+    let write_error = |err: io::Error| err.to_string();
+    let mut line = String::new();
+    while let ProgramState::Equation(equation_state) = &model.program_state {
+        let progress = &equation_state.equation_progress;
+        let completed = progress.equations_completed;
+        let number = completed + 1;
+        let question = &equation_state.current_equation;
+        match progress.number_equations {
+            Some(total) => write!(output, "{}/{}  {}", number, total, question),
+            None => write!(output, "{}  {}", number, question),
+        }
+        .map_err(write_error)?;
+        if question.answer_hint() != "Answer" {
+            write!(output, "  ({})", question.answer_hint()).map_err(write_error)?;
+        }
+        if let Some(time_remaining) = equation_state.time_remaining(model.timer.time_taken) {
+            let seconds = time_remaining.as_secs();
+            write!(output, "  [{}:{:02} left]", seconds / 60, seconds % 60).map_err(write_error)?;
+        }
+        write!(output, "\n> ").map_err(write_error)?;
+        output.flush().map_err(write_error)?;
+
+        line.clear();
+        let read = input.read_line(&mut line).map_err(|err| err.to_string())?;
+        let answer = line.trim();
+        if read == 0 || answer == QUIT_COMMAND {
+            // the input didn't end the line after the prompt
+            if read == 0 {
+                writeln!(output).map_err(write_error)?;
+            }
+            let _ = update(&mut model, Message::CancelEquation);
+            break;
+        }
+        let _ = update(&mut model, Message::Tick(Instant::now()));
+        let _ = update(&mut model, Message::ChangeAnswer(answer.to_string()));
+        let _ = update(&mut model, Message::SubmitAnswer);
+
+        let progress = match &model.program_state {
+            ProgramState::Equation(equation_state) => {
+                if let Some(err) = &equation_state.answer_error {
+                    writeln!(output, "{}", err).map_err(write_error)?;
+                    continue;
+                }
+                &equation_state.equation_progress
+            }
+            ProgramState::FinishedEquation(finished_state) => &finished_state.equation_progress,
+            _ => break,
+        };
+        // nothing was recorded if the time ran out before the answer was in
+        if progress.equations_completed > completed
+            && let Some(question_record) = progress.question_records.last()
+        {
+            write_result(&mut output, question_record).map_err(write_error)?;
+        }
+    }
+
+    if let ProgramState::FinishedEquation(finished_state) = &model.program_state {
+        let progress = &finished_state.equation_progress;
+        let seconds = finished_state.time_taken.as_secs();
+        write!(
+            output,
+            "Scored {}/{} in {}:{:02}",
+            progress.equations_success,
+            progress.equations_completed,
+            seconds / 60,
+            seconds % 60,
+        )
+        .map_err(write_error)?;
+        if let Some(rate) = progress.questions_per_minute(finished_state.time_taken) {
+            write!(output, ", {:.1} questions per minute", rate).map_err(write_error)?;
+        }
+        writeln!(output, "\nSeed {}", finished_state.seed).map_err(write_error)?;
+    }
+    Ok(())
+}
+
+fn write_result(output: &mut impl Write, question_record: &QuestionRecord) -> io::Result<()> {
+    if question_record.correct {
+        writeln!(output, "Right")
+    } else {
+        writeln!(
+            output,
+            "Wrong, the answer is {}",
+            question_record.correct_answer
+        )
+    }
+}
+
+fn run_worksheet(args: &[String]) -> Result<(), String> {
+    let options = parse_options(args)?;
+    // worksheets are always new questions, there's no sprint or review deck on paper
+    if options.game_mode.is_some() {
+        return Err("--mode only works when playing, not on a worksheet".to_string());
+    }
+    let worksheet = Worksheet::new(
+        &options.settings,
+        options.questions.unwrap_or(20),
+        options.columns.unwrap_or(2),
+        options.seed.unwrap_or_else(rand::random),
+    );
+    let output = options.output.unwrap_or_else(|| PathBuf::from("worksheet"));
+    println!("{}", crate::worksheet::save_to(&worksheet, &output)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equation::practice::PracticeSet;
+
+    fn args(text: &str) -> Vec<String> {
+        text.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parses_options() {
+        let options = parse_options(&args(
+            "--difficulty Times-Tables-2-12 --questions 30 --seed 7",
+        ))
+        .unwrap();
+        assert_eq!(
+            options.settings.equation_difficulty,
+            EquationDifficulty::Practice(PracticeSet::TimesTables)
+        );
+        assert_eq!(options.questions, Some(30));
+        assert_eq!(options.seed, Some(7));
+        assert_eq!(options.columns, None);
+
+        assert_eq!(
+            parse_options(&args("--questions many")).unwrap_err(),
+            "--questions needs a number, not 'many'"
+        );
+        assert!(
+            parse_options(&args("--questions 0"))
+                .unwrap_err()
+                .starts_with("--questions needs to be at least 1")
+        );
+        assert_eq!(
+            run_play(&args("--output sheet"), io::empty(), io::sink()).unwrap_err(),
+            "--columns and --output only work on a worksheet, not when playing"
+        );
+        assert_eq!(parse_difficulty("custom"), Ok(EquationDifficulty::Custom));
+        assert_eq!(
+            parse_options(&args("--seed")).unwrap_err(),
+            "--seed needs a value"
+        );
+        assert!(parse_difficulty("impossible").is_err());
+        assert_eq!(parse_game_mode("review-mistakes"), Ok(GameMode::Review));
+        assert_eq!(
+            run_worksheet(&args("--mode sprint")).unwrap_err(),
+            "--mode only works when playing, not on a worksheet"
+        );
+        assert_eq!(
+            run_play(&args("--questions 5000000000"), io::empty(), io::sink()).unwrap_err(),
+            "--questions can be at most 4294967295"
+        );
+    }
+}
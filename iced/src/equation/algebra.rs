@@ -0,0 +1,408 @@
+// solve-for-x questions, built backwards from the value of x like the medium equations
+
+use std::fmt::Display;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Equation,
+    answer::{Answer, AnswerParseError, parse_answer},
+    layout::{MathBox, MathLayout},
+    rational::Rational,
+    saved::SavedQuestion,
+};
+
+/// The shape of a linear equation, `a`, `b` and `c` are the known numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LinearForm {
+    /// `ax + b = c`
+    Expanded,
+    /// `a(x + b) = c`
+    Bracketed,
+    /// `x/a + b = c`
+    Divided,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearEquation {
+    form: LinearForm,
+    a: Rational,
+    b: Rational,
+    c: Rational,
+    x: Rational,
+}
+
+/// `x² + bx + c = 0` with whole number roots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuadraticEquation {
+    b: Rational,
+    c: Rational,
+    roots: [Rational; 2],
+}
+
+/// The value of x, which can be written as `4` or `x = 4`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Solution(pub Rational);
+
+/// Every value of x in any order, e.g. `2, -3` or `x = 2 or x = -3`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solutions(Vec<Rational>);
+
+impl Solutions {
+    /// Sorts the values and drops repeats so they compare the same however they're written
+    pub fn new(mut values: Vec<Rational>) -> Self {
+        values.sort();
+        values.dedup();
+        Self(values)
+    }
+}
+
+/// A linear equation whose answer is a whole number, the coefficient of x is sometimes a half
+pub fn new_linear_equation<R: Rng + ?Sized>(rng: &mut R) -> LinearEquation {
+    let x = Rational::from(rng.random_range(-10..=10));
+    let b = Rational::from(nonzero(rng, 20));
+    let form = match rng.random_range(0..3) {
+        0 => LinearForm::Expanded,
+        1 => LinearForm::Bracketed,
+        _ => LinearForm::Divided,
+    };
+    match form {
+        LinearForm::Expanded => {
+            let mut a = Rational::from(nonzero(rng, 9));
+            // a half makes 3.5x, keeping x even so the other side stays whole
+            let x = if rng.random_bool(0.25) {
+                a = a + Rational::new(a.numerator().signum(), 2);
+                x * Rational::from(2)
+            } else {
+                x
+            };
+            LinearEquation::new(form, a, b, a * x + b, x)
+        }
+        LinearForm::Bracketed => {
+            let sign = if rng.random() { 1 } else { -1 };
+            let a = Rational::from(sign * rng.random_range(2..=9));
+            LinearEquation::new(form, a, b, a * (x + b), x)
+        }
+        LinearForm::Divided => {
+            let a = Rational::from(rng.random_range(2..=9));
+            // x is a multiple of a so x/a is whole
+            let x = x * a;
+            LinearEquation::new(form, a, b, x / a + b, x)
+        }
+    }
+}
+
+/// A quadratic which factors into `(x - p)(x - q)` with whole number roots
+pub fn new_quadratic_equation<R: Rng + ?Sized>(rng: &mut R) -> QuadraticEquation {
+    let p = Rational::from(rng.random_range(-9..=9));
+    let q = Rational::from(rng.random_range(-9..=9));
+    QuadraticEquation {
+        b: -(p + q),
+        c: p * q,
+        roots: [p, q],
+    }
+}
+
+/// A random number from `-max` to `max` which isn't zero
+fn nonzero<R: Rng + ?Sized>(rng: &mut R, max: i64) -> i64 {
+    let value = rng.random_range(1..=max);
+    if rng.random() { value } else { -value }
+}
+
+impl LinearEquation {
+    fn new(form: LinearForm, a: Rational, b: Rational, c: Rational, x: Rational) -> Self {
+        Self { form, a, b, c, x }
+    }
+
+    /// Whether x really is the answer, worked out without panicking since a loaded equation
+    /// could have anything in it
+    pub(super) fn works_out(&self) -> bool {
+        let left = match self.form {
+            LinearForm::Expanded => self
+                .a
+                .checked_mul(self.x)
+                .and_then(|ax| ax.checked_add(self.b)),
+            LinearForm::Bracketed => self
+                .x
+                .checked_add(self.b)
+                .and_then(|sum| self.a.checked_mul(sum)),
+            LinearForm::Divided => self
+                .x
+                .checked_div(self.a)
+                .and_then(|quotient| quotient.checked_add(self.b)),
+        };
+        // b is written as a sign and its size
+        left == Some(self.c) && self.b.checked_neg().is_some()
+    }
+}
+
+impl Equation for LinearEquation {
+    type Answer = Solution;
+
+    fn answer(&self) -> Solution {
+        Solution(self.x)
+    }
+
+    fn saved(&self) -> Option<SavedQuestion> {
+        Some(SavedQuestion::Linear(self.clone()))
+    }
+}
+
+impl Equation for QuadraticEquation {
+    type Answer = Solutions;
+
+    fn answer(&self) -> Solutions {
+        Solutions::new(self.roots.to_vec())
+    }
+
+    fn saved(&self) -> Option<SavedQuestion> {
+        Some(SavedQuestion::Quadratic(self.clone()))
+    }
+}
+
+/// Removes an `x =` written before a value
+fn strip_unknown(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_prefix(['x', 'X'])
+        .and_then(|rest| rest.trim_start().strip_prefix('='))
+        .unwrap_or(text)
+}
+
+impl Answer for Solution {
+    fn parse(text: &str) -> Result<Self, AnswerParseError> {
+        parse_answer(strip_unknown(text)).map(Solution)
+    }
+
+    fn hint() -> &'static str {
+        "Value of x"
+    }
+}
+
+impl Answer for Solutions {
+    fn parse(text: &str) -> Result<Self, AnswerParseError> {
+        let text = text.to_lowercase();
+        // a comma only separates values with a space after it, so `1,000` is still a thousand
+        let values = text
+            .split(';')
+            .flat_map(|part| part.split(", "))
+            .flat_map(|part| part.split(" or "))
+            .flat_map(|part| part.split(" and "))
+            .map(|part| parse_answer(strip_unknown(part)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Solutions::new(values))
+    }
+
+    fn hint() -> &'static str {
+        "Every value of x, e.g. 2, -3"
+    }
+}
+
+/// A term like `3.5x`, leaving out a coefficient of one
+fn unknown_term(coefficient: Rational) -> String {
+    if coefficient == Rational::ONE {
+        "x".to_string()
+    } else if coefficient == -Rational::ONE {
+        "-x".to_string()
+    } else {
+        format!("{}x", coefficient)
+    }
+}
+
+/// `+ b` or `- b` for a term after the first
+fn signed_term(value: Rational) -> (&'static str, Rational) {
+    if value < Rational::ZERO {
+        ("-", -value)
+    } else {
+        ("+", value)
+    }
+}
+
+impl MathLayout for LinearEquation {
+    fn math_layout(&self) -> MathBox {
+        let (sign, b) = signed_term(self.b);
+        let left = match self.form {
+            LinearForm::Expanded => MathBox::text(unknown_term(self.a)),
+            LinearForm::Bracketed => MathBox::Row(vec![
+                MathBox::text(self.a.to_string()),
+                MathBox::Brackets(Box::new(MathBox::Row(vec![
+                    MathBox::text("x"),
+                    MathBox::text(sign),
+                    MathBox::text(b.to_string()),
+                ]))),
+            ]),
+            LinearForm::Divided => MathBox::Fraction(
+                Box::new(MathBox::text("x")),
+                Box::new(MathBox::text(self.a.to_string())),
+            ),
+        };
+        let mut row = vec![left];
+        if self.form != LinearForm::Bracketed {
+            row.extend([MathBox::text(sign), MathBox::text(b.to_string())]);
+        }
+        row.extend([MathBox::text("="), MathBox::text(self.c.to_string())]);
+        MathBox::Row(row)
+    }
+}
+
+impl Display for LinearEquation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (sign, b) = signed_term(self.b);
+        match self.form {
+            LinearForm::Expanded => write!(f, "{} {} {}", unknown_term(self.a), sign, b)?,
+            LinearForm::Bracketed => write!(f, "{}(x {} {})", self.a, sign, b)?,
+            LinearForm::Divided => write!(f, "x/{} {} {}", self.a, sign, b)?,
+        }
+        write!(f, " = {}", self.c)
+    }
+}
+
+impl QuadraticEquation {
+    /// Whether both roots solve the equation, worked out without panicking
+    pub(super) fn works_out(&self) -> bool {
+        let solves = |root: Rational| {
+            let square = root.checked_pow(2)?;
+            let linear = self.b.checked_mul(root)?;
+            square.checked_add(linear)?.checked_add(self.c)
+        };
+        // b and c are written as a sign and their size
+        self.roots
+            .into_iter()
+            .all(|root| solves(root) == Some(Rational::ZERO))
+            && self.b.checked_neg().is_some()
+            && self.c.checked_neg().is_some()
+    }
+
+    /// The terms after `x²` which aren't zero, as a sign and the size of the term
+    fn lower_terms(&self) -> Vec<(&'static str, String)> {
+        let mut terms = Vec::new();
+        if !self.b.is_zero() {
+            let (sign, b) = signed_term(self.b);
+            terms.push((sign, unknown_term(b)));
+        }
+        if !self.c.is_zero() {
+            let (sign, c) = signed_term(self.c);
+            terms.push((sign, c.to_string()));
+        }
+        terms
+    }
+}
+
+impl MathLayout for QuadraticEquation {
+    fn math_layout(&self) -> MathBox {
+        let mut row = vec![MathBox::Power(
+            Box::new(MathBox::text("x")),
+            Box::new(MathBox::text("2")),
+        )];
+        for (sign, term) in self.lower_terms() {
+            row.extend([MathBox::text(sign), MathBox::text(term)]);
+        }
+        row.extend([MathBox::text("="), MathBox::text("0")]);
+        MathBox::Row(row)
+    }
+}
+
+impl Display for QuadraticEquation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "x²")?;
+        for (sign, term) in self.lower_terms() {
+            write!(f, " {} {}", sign, term)?;
+        }
+        write!(f, " = 0")
+    }
+}
+
+impl Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "x = {}", self.0)
+    }
+}
+
+impl Display for Solutions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let values = self
+            .0
+            .iter()
+            .map(|value| format!("x = {}", value))
+            .collect::<Vec<_>>();
+        write!(f, "{}", values.join(" or "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::equation::Question;
+
+    #[test]
+    fn writes_and_checks_linear_equations() {
+        let equation = LinearEquation::new(
+            LinearForm::Expanded,
+            Rational::new(7, 2),
+            Rational::from(-2),
+            Rational::from(12),
+            Rational::from(4),
+        );
+        assert_eq!(equation.to_string(), "3.5x - 2 = 12");
+        assert_eq!(equation.to_latex(), "3.5x - 2 = 12");
+        assert!(
+            equation
+                .to_mathml()
+                .contains("<mrow><mn>3.5</mn><mo>&#x2062;</mo><mi>x</mi></mrow>")
+        );
+        assert_eq!(equation.check_answer("4"), Ok(true));
+        assert_eq!(equation.check_answer("x = 4"), Ok(true));
+        assert_eq!(equation.check_answer("x=3"), Ok(false));
+    }
+
+    #[test]
+    fn checks_quadratic_roots_in_any_order() {
+        let equation = QuadraticEquation {
+            b: Rational::ONE,
+            c: Rational::from(-6),
+            roots: [Rational::from(2), Rational::from(-3)],
+        };
+        assert_eq!(equation.to_string(), "x² + x - 6 = 0");
+        assert_eq!(equation.answer_text(), "x = -3 or x = 2");
+        assert_eq!(equation.check_answer("2, -3"), Ok(true));
+        assert_eq!(equation.check_answer("x = -3 or x = 2"), Ok(true));
+        assert_eq!(equation.check_answer("2"), Ok(false));
+    }
+
+    #[test]
+    fn reads_thousands_separators_in_solutions() {
+        let equation = QuadraticEquation {
+            b: Rational::from(-998),
+            c: Rational::from(-2000),
+            roots: [Rational::from(1000), Rational::from(-2)],
+        };
+        assert_eq!(equation.check_answer("x = 1,000 or x = -2"), Ok(true));
+        assert_eq!(equation.check_answer("1,000, -2"), Ok(true));
+        assert_eq!(equation.check_answer("-2; 1,000"), Ok(true));
+        assert_eq!(equation.check_answer("1,000"), Ok(false));
+    }
+
+    #[test]
+    fn generated_equations_hold_for_their_answer() {
+        let mut rng = StdRng::seed_from_u64(18);
+        for _ in 0..100 {
+            let equation = new_linear_equation(&mut rng);
+            let x = equation.x;
+            let left = match equation.form {
+                LinearForm::Expanded => equation.a * x + equation.b,
+                LinearForm::Bracketed => equation.a * (x + equation.b),
+                LinearForm::Divided => x / equation.a + equation.b,
+            };
+            assert_eq!(left, equation.c, "{}", equation);
+            assert!(equation.c.is_integer(), "{}", equation);
+
+            let quadratic = new_quadratic_equation(&mut rng);
+            for root in quadratic.roots {
+                assert!((root * root + quadratic.b * root + quadratic.c).is_zero());
+            }
+        }
+    }
+}
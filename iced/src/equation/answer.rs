@@ -0,0 +1,463 @@
+// parsing of the answers typed in by the player
+
+use std::{fmt::Display, iter::Peekable, str::Chars};
+
+use super::{medium::parse_tokens, rational::Rational};
+
+/// The type of a question's answer, read from the text the player types in
+pub trait Answer: Sized + PartialEq + Display {
+    fn parse(text: &str) -> Result<Self, AnswerParseError>;
+
+    /// Shown in the empty answer box
+    fn hint() -> &'static str {
+        "Answer"
+    }
+}
+
+impl Answer for Rational {
+    fn parse(text: &str) -> Result<Self, AnswerParseError> {
+        parse_answer(text)
+    }
+}
+
+/// Parses an answer typed in by the player into its exact value.
+///
+/// As well as plain decimals this accepts fractions (`3/4`), mixed numbers (`1 1/2`),
+/// scientific notation (`-1.5e1`), thousands separators (`1,000`) and simple arithmetic
+/// (`2*3`, `(1+2)×3`, `√16`, `∛27`, `3²`, `2⁵`, `|-2|`).
+pub fn parse_answer(text: &str) -> Result<Rational, AnswerParseError> {
+    let tokens = tokenize(text)?;
+    if tokens.is_empty() {
+        return Err(AnswerParseError::Empty);
+    }
+
+    parse_tokens(tokens, true)?.checked_value()
+}
+
+/// Parses an answer made of a quotient and remainder, such as `3 r 2`, `3r2` or
+/// `3 remainder 2`. Each part can be written any way [`parse_answer`] accepts.
+pub fn parse_quotient_remainder(text: &str) -> Result<(Rational, Rational), AnswerParseError> {
+    let text = text.to_lowercase();
+    let Some(word_start) = text.find(char::is_alphabetic) else {
+        return Err(if text.trim().is_empty() {
+            AnswerParseError::Empty
+        } else {
+            AnswerParseError::MissingRemainder
+        });
+    };
+    let word_end = text[word_start..]
+        .find(|character: char| !character.is_alphabetic())
+        .map_or(text.len(), |length| word_start + length);
+    let word = &text[word_start..word_end];
+    if !["r", "rem", "remainder"].contains(&word) {
+        return Err(AnswerParseError::UnexpectedToken(word.to_string()));
+    }
+
+    let quotient = parse_answer(&text[..word_start])?;
+    let remainder = parse_answer(&text[word_end..]).map_err(|err| match err {
+        AnswerParseError::Empty => AnswerParseError::MissingRemainder,
+        err => err,
+    })?;
+    Ok((quotient, remainder))
+}
+
+/// The reason an answer couldn't be understood
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnswerParseError {
+    Empty,
+    UnexpectedCharacter(char),
+    InvalidNumber(String),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnbalancedBrackets,
+    DivideByZero,
+    TooLarge,
+    /// Brackets or operators nested too deeply to work out
+    TooDeep,
+    InexactRoot,
+    MissingRemainder,
+    NotAFraction,
+}
+
+impl Display for AnswerParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnswerParseError::Empty => write!(f, "Enter an answer first"),
+            AnswerParseError::UnexpectedCharacter(character) => {
+                write!(f, "Didn't expect '{}' in the answer", character)
+            }
+            AnswerParseError::InvalidNumber(number) => write!(f, "'{}' isn't a number", number),
+            AnswerParseError::UnexpectedToken(token) => {
+                write!(f, "Didn't expect '{}' there", token)
+            }
+            AnswerParseError::UnexpectedEnd => write!(f, "The answer looks unfinished"),
+            AnswerParseError::UnbalancedBrackets => write!(f, "The brackets don't match up"),
+            AnswerParseError::DivideByZero => write!(f, "Can't divide by zero"),
+            AnswerParseError::TooLarge => write!(f, "That number is too big"),
+            AnswerParseError::TooDeep => write!(f, "That's too long to work out"),
+            AnswerParseError::InexactRoot => {
+                write!(f, "Can only take roots of perfect squares and cubes")
+            }
+            AnswerParseError::MissingRemainder => {
+                write!(f, "Write the answer with a remainder, e.g. 3 r 2")
+            }
+            AnswerParseError::NotAFraction => {
+                write!(f, "Write the answer as one fraction, e.g. 3/4")
+            }
+        }
+    }
+}
+
+/// Superscript digits in order, used to write powers like `2⁵`
+pub const SUPERSCRIPT_DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Token {
+    Number {
+        value: Rational,
+        is_integer: bool,
+        spaced: bool,
+    },
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    OpenBracket,
+    CloseBracket,
+    AbsoluteBar,
+    SquareRoot,
+    CubeRoot,
+    /// An exponent written in superscript digits
+    Power(u32),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number { value, .. } => write!(f, "{}", value),
+            Token::Add => write!(f, "+"),
+            Token::Subtract => write!(f, "-"),
+            Token::Multiply => write!(f, "×"),
+            Token::Divide => write!(f, "/"),
+            Token::OpenBracket => write!(f, "("),
+            Token::CloseBracket => write!(f, ")"),
+            Token::AbsoluteBar => write!(f, "|"),
+            Token::SquareRoot => write!(f, "√"),
+            Token::CubeRoot => write!(f, "∛"),
+            Token::Power(exponent) => {
+                let string: String = exponent
+                    .to_string()
+                    .chars()
+                    .map(|digit| SUPERSCRIPT_DIGITS[digit.to_digit(10).unwrap_or(0) as usize])
+                    .collect();
+                write!(f, "{}", string)
+            }
+        }
+    }
+}
+
+pub(super) fn tokenize(text: &str) -> Result<Vec<Token>, AnswerParseError> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    // whether there was whitespace before the current token, used for mixed numbers
+    let mut spaced = false;
+
+    while let Some(&character) = chars.peek() {
+        let token = match character {
+            c if c.is_whitespace() => {
+                chars.next();
+                spaced = true;
+                continue;
+            }
+            '0'..='9' | '.' => read_number(&mut chars, spaced)?,
+            '+' => Token::Add,
+            '-' | '−' => Token::Subtract,
+            '*' | '×' => Token::Multiply,
+            '/' | '÷' => Token::Divide,
+            '(' => Token::OpenBracket,
+            ')' => Token::CloseBracket,
+            '|' => Token::AbsoluteBar,
+            '√' => Token::SquareRoot,
+            '∛' => Token::CubeRoot,
+            c if SUPERSCRIPT_DIGITS.contains(&c) => read_superscript(&mut chars)?,
+            c => return Err(AnswerParseError::UnexpectedCharacter(c)),
+        };
+        if !matches!(token, Token::Number { .. } | Token::Power(_)) {
+            chars.next();
+        }
+        tokens.push(token);
+        spaced = false;
+    }
+
+    Ok(tokens)
+}
+
+fn read_superscript(chars: &mut Peekable<Chars>) -> Result<Token, AnswerParseError> {
+    let mut exponent: u32 = 0;
+    while let Some(digit) = chars.peek().and_then(|character| {
+        SUPERSCRIPT_DIGITS
+            .iter()
+            .position(|digit| digit == character)
+    }) {
+        exponent = exponent
+            .checked_mul(10)
+            .and_then(|exponent| exponent.checked_add(digit as u32))
+            .ok_or(AnswerParseError::TooLarge)?;
+        chars.next();
+    }
+    Ok(Token::Power(exponent))
+}
+
+fn read_number(chars: &mut Peekable<Chars>, spaced: bool) -> Result<Token, AnswerParseError> {
+    let mut number_text = String::new();
+    let mut digits = String::new();
+    let mut decimal_places = 0;
+    let mut is_integer = true;
+
+    // integer part, possibly with thousands separators
+    let mut group_length = 0;
+    let mut has_separator = false;
+    while let Some(&character) = chars.peek() {
+        match character {
+            '0'..='9' => {
+                digits.push(character);
+                group_length += 1;
+            }
+            ',' => {
+                // the first group can be 1-3 digits, every group after must be 3
+                if group_length == 0 || group_length > 3 || (has_separator && group_length != 3) {
+                    number_text.push(character);
+                    return Err(AnswerParseError::InvalidNumber(number_text));
+                }
+                has_separator = true;
+                group_length = 0;
+            }
+            _ => break,
+        }
+        number_text.push(character);
+        chars.next();
+    }
+    if has_separator && group_length != 3 {
+        return Err(AnswerParseError::InvalidNumber(number_text));
+    }
+
+    // fractional part
+    if chars.peek() == Some(&'.') {
+        is_integer = false;
+        number_text.push('.');
+        chars.next();
+        while let Some(&character) = chars.peek() {
+            if !character.is_ascii_digit() {
+                break;
+            }
+            digits.push(character);
+            decimal_places += 1;
+            number_text.push(character);
+            chars.next();
+        }
+    }
+
+    // exponent
+    let mut exponent = String::new();
+    if matches!(chars.peek(), Some('e' | 'E')) {
+        is_integer = false;
+        number_text.push('e');
+        chars.next();
+        if let Some(&sign @ ('+' | '-')) = chars.peek() {
+            exponent.push(sign);
+            number_text.push(sign);
+            chars.next();
+        }
+        while let Some(&character) = chars.peek() {
+            if !character.is_ascii_digit() {
+                break;
+            }
+            exponent.push(character);
+            number_text.push(character);
+            chars.next();
+        }
+    }
+
+    if digits.is_empty()
+        || (number_text.contains('e') && exponent.trim_start_matches(['+', '-']).is_empty())
+    {
+        return Err(AnswerParseError::InvalidNumber(number_text));
+    }
+    let mantissa = digits
+        .parse::<i64>()
+        .map_err(|_| AnswerParseError::TooLarge)?;
+    let exponent = if exponent.is_empty() {
+        0
+    } else {
+        exponent
+            .parse::<i32>()
+            .map_err(|_| AnswerParseError::TooLarge)?
+    };
+
+    // value = mantissa * 10^(exponent - decimal places)
+    let power = exponent
+        .checked_sub(decimal_places)
+        .ok_or(AnswerParseError::TooLarge)?;
+    let scale = 10i64
+        .checked_pow(power.unsigned_abs())
+        .ok_or(AnswerParseError::TooLarge)?;
+    let value = if power >= 0 {
+        Rational::from(mantissa).checked_mul(Rational::from(scale))
+    } else {
+        Some(Rational::new(mantissa, scale))
+    }
+    .ok_or(AnswerParseError::TooLarge)?;
+
+    Ok(Token::Number {
+        value,
+        is_integer,
+        spaced,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Display;
+
+    use super::{AnswerParseError, parse_answer, parse_quotient_remainder};
+    use crate::equation::{
+        Equation, Evaluate,
+        layout::{MathBox, MathLayout},
+        rational::Rational,
+    };
+
+    #[derive(Debug)]
+    struct FixedEquation(Rational);
+
+    impl Display for FixedEquation {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl MathLayout for FixedEquation {
+        fn math_layout(&self) -> MathBox {
+            MathBox::text(self.0.to_string())
+        }
+    }
+
+    impl Evaluate for FixedEquation {
+        fn calc_value(&self) -> Rational {
+            self.0
+        }
+    }
+
+    fn accepts(expected: Rational, text: &str) {
+        let value = parse_answer(text).unwrap_or_else(|err| panic!("{text}: {err}"));
+        assert!(
+            FixedEquation(expected).check(&value),
+            "{text} parsed to {value}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn parses_plain_decimals() {
+        accepts(Rational::new(123, 10), "12.3");
+        accepts(Rational::from(-4), "-4");
+        accepts(Rational::new(1, 2), ".5");
+        accepts(Rational::from(7), "  7 ");
+    }
+
+    #[test]
+    fn parses_fractions_and_mixed_numbers() {
+        accepts(Rational::new(3, 4), "3/4");
+        accepts(Rational::new(-3, 4), "-3/4");
+        accepts(Rational::new(3, 2), "1 1/2");
+        accepts(Rational::new(-9, 4), "-2 1/4");
+        accepts(Rational::new(1, 3), "1÷3");
+    }
+
+    #[test]
+    fn parses_scientific_notation() {
+        accepts(Rational::from(-15), "-1.5e1");
+        accepts(Rational::new(25, 1000), "2.5E-2");
+        accepts(Rational::from(300), "3e+2");
+    }
+
+    #[test]
+    fn parses_thousands_separators() {
+        accepts(Rational::from(1000), "1,000");
+        accepts(Rational::new(-2469135, 2), "-1,234,567.5");
+    }
+
+    #[test]
+    fn parses_arithmetic() {
+        accepts(Rational::from(6), "2*3");
+        accepts(Rational::from(6), "2×3");
+        accepts(Rational::from(9), "(1+2)×3");
+        accepts(Rational::from(7), "1+2*3");
+        accepts(Rational::from(-1), "2 − 3");
+        accepts(Rational::from(5), "--5");
+        accepts(Rational::new(3, 10), "0.1+0.2");
+        accepts(Rational::from(-9), "-3²");
+        accepts(Rational::from(9), "(-3)²");
+        accepts(Rational::from(-8), "(-2)³");
+        accepts(Rational::new(3, 2), "√2.25");
+        accepts(Rational::from(-3), "∛-27");
+        accepts(Rational::from(32), "2⁵");
+        accepts(Rational::from(1), "7⁰");
+        accepts(Rational::from(1), "1⁴⁰⁰⁰⁰⁰⁰⁰⁰⁰");
+        accepts(Rational::from(7), "√(9+16) + |-2|");
+        accepts(Rational::from(1), "||-3| - 4|");
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(parse_answer(""), Err(AnswerParseError::Empty));
+        assert_eq!(parse_answer("   "), Err(AnswerParseError::Empty));
+        assert_eq!(
+            parse_answer("abc"),
+            Err(AnswerParseError::UnexpectedCharacter('a'))
+        );
+        assert_eq!(
+            parse_answer("1,00"),
+            Err(AnswerParseError::InvalidNumber("1,00".to_string()))
+        );
+        assert_eq!(parse_answer("2*"), Err(AnswerParseError::UnexpectedEnd));
+        assert_eq!(
+            parse_answer("(1+2"),
+            Err(AnswerParseError::UnbalancedBrackets)
+        );
+        assert_eq!(
+            parse_answer("1+2)"),
+            Err(AnswerParseError::UnbalancedBrackets)
+        );
+        assert_eq!(parse_answer("1/0"), Err(AnswerParseError::DivideByZero));
+        assert_eq!(parse_answer("1e99"), Err(AnswerParseError::TooLarge));
+        assert_eq!(
+            parse_answer("1.5e-2147483648"),
+            Err(AnswerParseError::TooLarge)
+        );
+        assert_eq!(parse_answer("√2"), Err(AnswerParseError::InexactRoot));
+        assert_eq!(parse_answer("2⁴⁰⁰⁰⁰⁰⁰⁰⁰⁰"), Err(AnswerParseError::TooLarge));
+        assert!(matches!(
+            parse_answer("1 2"),
+            Err(AnswerParseError::UnexpectedToken(_))
+        ));
+    }
+
+    #[test]
+    fn parses_quotients_and_remainders() {
+        let expected = Ok((Rational::from(3), Rational::from(2)));
+        assert_eq!(parse_quotient_remainder("3 r 2"), expected);
+        assert_eq!(parse_quotient_remainder("3R2"), expected);
+        assert_eq!(parse_quotient_remainder(" 3 remainder 2 "), expected);
+        assert_eq!(
+            parse_quotient_remainder("3"),
+            Err(AnswerParseError::MissingRemainder)
+        );
+        assert_eq!(
+            parse_quotient_remainder("3 r"),
+            Err(AnswerParseError::MissingRemainder)
+        );
+        assert_eq!(
+            parse_quotient_remainder("3 x 2"),
+            Err(AnswerParseError::UnexpectedToken("x".to_string()))
+        );
+    }
+}
@@ -0,0 +1,138 @@
+// settings for how equations are generated
+
+use serde::{Deserialize, Serialize};
+
+/// How often each double operator is picked relative to the others, zero means never
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperatorWeights {
+    pub add: u32,
+    pub subtract: u32,
+    pub multiply: u32,
+    pub divide: u32,
+}
+
+impl OperatorWeights {
+    pub const EVEN: OperatorWeights = OperatorWeights {
+        add: 1,
+        subtract: 1,
+        multiply: 1,
+        divide: 1,
+    };
+}
+
+/// Settings for generating equations
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeneratorConfig {
+    /// The fewest levels of operators below the top one
+    pub min_depth: i32,
+    pub max_depth: i32,
+    /// The smallest random operands can be
+    pub min_operand: i64,
+    /// The largest random operands can be
+    pub max_operand: i64,
+    /// The decimal places random operands are given
+    pub decimal_places: u32,
+    pub operator_weights: OperatorWeights,
+    /// The chance of using an operator like √ or ² instead of one with two operands
+    pub single_operator_chance: f64,
+    /// Whether negative numbers can show up anywhere in the equation or the answer
+    pub allow_negatives: bool,
+}
+
+impl GeneratorConfig {
+    /// A single +, - or × with whole numbers up to 10
+    pub const SIMPLE: GeneratorConfig = GeneratorConfig {
+        min_depth: 0,
+        max_depth: 0,
+        min_operand: -10,
+        max_operand: 10,
+        decimal_places: 0,
+        operator_weights: OperatorWeights {
+            add: 1,
+            subtract: 1,
+            multiply: 1,
+            divide: 0,
+        },
+        single_operator_chance: 0.0,
+        allow_negatives: true,
+    };
+
+    pub const MEDIUM: GeneratorConfig = GeneratorConfig {
+        min_depth: 1,
+        max_depth: 1,
+        min_operand: -50,
+        max_operand: 50,
+        decimal_places: 1,
+        operator_weights: OperatorWeights::EVEN,
+        single_operator_chance: 0.25,
+        allow_negatives: true,
+    };
+
+    pub const COMPLEX: GeneratorConfig = GeneratorConfig {
+        min_depth: 2,
+        max_depth: 3,
+        ..GeneratorConfig::MEDIUM
+    };
+
+    /// The deepest trees which can be generated before they get too big to read
+    pub const MAX_DEPTH: i32 = 4;
+
+    /// The largest operands can be either side of zero
+    pub const MAX_OPERAND: i64 = 1000;
+
+    pub const MAX_DECIMAL_PLACES: u32 = 3;
+
+    /// Brings every setting into a range the generator can work with
+    pub fn validated(self) -> Self {
+        let min_depth = self.min_depth.clamp(0, Self::MAX_DEPTH);
+        let lowest_operand = if self.allow_negatives {
+            -Self::MAX_OPERAND
+        } else {
+            0
+        };
+        let min_operand = self
+            .min_operand
+            .clamp(lowest_operand, Self::MAX_OPERAND - 1);
+        Self {
+            min_depth,
+            max_depth: self.max_depth.clamp(min_depth, Self::MAX_DEPTH),
+            min_operand,
+            max_operand: self.max_operand.clamp(min_operand + 1, Self::MAX_OPERAND),
+            decimal_places: self.decimal_places.min(Self::MAX_DECIMAL_PLACES),
+            single_operator_chance: self.single_operator_chance.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self::MEDIUM
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GeneratorConfig;
+
+    #[test]
+    fn validates_ranges() {
+        let config = GeneratorConfig {
+            min_depth: 3,
+            max_depth: 1,
+            min_operand: -20,
+            max_operand: -30,
+            decimal_places: 9,
+            allow_negatives: false,
+            ..GeneratorConfig::MEDIUM
+        }
+        .validated();
+        assert_eq!((config.min_depth, config.max_depth), (3, 3));
+        assert_eq!((config.min_operand, config.max_operand), (0, 1));
+        assert_eq!(config.decimal_places, GeneratorConfig::MAX_DECIMAL_PLACES);
+        assert_eq!(
+            GeneratorConfig::COMPLEX.validated(),
+            GeneratorConfig::COMPLEX
+        );
+    }
+}
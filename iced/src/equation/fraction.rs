@@ -0,0 +1,422 @@
+// fraction arithmetic and simplifying, answered in lowest terms
+
+use std::fmt::Display;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Equation,
+    answer::{Answer, AnswerParseError, Token, tokenize},
+    layout::{MathBox, MathLayout},
+    rational::Rational,
+    saved::SavedQuestion,
+};
+
+/// A fraction as it's written in the question, not reduced like a [`Rational`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct WrittenFraction {
+    numerator: i64,
+    denominator: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FractionOperator {
+    Add,
+    Subtract,
+    Multiply,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FractionQuestion {
+    /// `a/b ± c/d` or `a/b × c/d`
+    Operation(WrittenFraction, FractionOperator, WrittenFraction),
+    /// A fraction which isn't in lowest terms, to be simplified
+    Simplify(WrittenFraction),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FractionEquation {
+    question: FractionQuestion,
+    /// Whether an equivalent terminating decimal counts as well as a fraction
+    accept_decimals: bool,
+}
+
+/// An answer to a fraction question along with how it was written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FractionAnswer {
+    pub value: Rational,
+    /// Every fraction written in the answer is in lowest terms, whole numbers count too
+    pub lowest_terms: bool,
+    pub decimal: bool,
+}
+
+/// A proper fraction with a denominator from 2 to 12
+fn random_fraction<R: Rng + ?Sized>(rng: &mut R) -> WrittenFraction {
+    let denominator = rng.random_range(2..=12);
+    WrittenFraction {
+        numerator: rng.random_range(1..denominator),
+        denominator,
+    }
+}
+
+/// Adding, subtracting or multiplying two proper fractions
+pub fn new_fraction_equation<R: Rng + ?Sized>(
+    rng: &mut R,
+    accept_decimals: bool,
+) -> FractionEquation {
+    let operator = match rng.random_range(0..3) {
+        0 => FractionOperator::Add,
+        1 => FractionOperator::Subtract,
+        _ => FractionOperator::Multiply,
+    };
+    FractionEquation {
+        question: FractionQuestion::Operation(random_fraction(rng), operator, random_fraction(rng)),
+        accept_decimals,
+    }
+}
+
+/// A fraction in lowest terms scaled up by 2 to 6, e.g. `12/18`
+pub fn new_simplify_equation<R: Rng + ?Sized>(
+    rng: &mut R,
+    accept_decimals: bool,
+) -> FractionEquation {
+    let simplified = Rational::from(random_fraction(rng));
+    let scale = rng.random_range(2..=6);
+    FractionEquation {
+        question: FractionQuestion::Simplify(WrittenFraction {
+            numerator: simplified.numerator() * scale,
+            denominator: simplified.denominator() * scale,
+        }),
+        accept_decimals,
+    }
+}
+
+impl From<WrittenFraction> for Rational {
+    fn from(fraction: WrittenFraction) -> Self {
+        Rational::new(fraction.numerator, fraction.denominator)
+    }
+}
+
+impl WrittenFraction {
+    /// The fraction's value, `None` if it has a zero denominator or doesn't fit
+    fn checked_value(&self) -> Option<Rational> {
+        Rational::from(self.numerator).checked_div(Rational::from(self.denominator))
+    }
+}
+
+impl FractionEquation {
+    /// The answer worked out without panicking, `None` for a loaded question which divides by
+    /// zero or overflows
+    fn checked_value(&self) -> Option<Rational> {
+        match self.question {
+            FractionQuestion::Operation(left, operator, right) => {
+                let (left, right) = (left.checked_value()?, right.checked_value()?);
+                match operator {
+                    FractionOperator::Add => left.checked_add(right),
+                    FractionOperator::Subtract => left.checked_sub(right),
+                    FractionOperator::Multiply => left.checked_mul(right),
+                }
+            }
+            FractionQuestion::Simplify(fraction) => fraction.checked_value(),
+        }
+    }
+
+    pub(super) fn works_out(&self) -> bool {
+        self.checked_value().is_some()
+    }
+}
+
+impl Equation for FractionEquation {
+    type Answer = FractionAnswer;
+
+    fn answer(&self) -> FractionAnswer {
+        FractionAnswer {
+            value: self
+                .checked_value()
+                .expect("fraction questions should be checked when they're loaded"),
+            lowest_terms: true,
+            decimal: false,
+        }
+    }
+
+    fn check(&self, answer: &FractionAnswer) -> bool {
+        answer.value == self.answer().value
+            && (answer.lowest_terms || (self.accept_decimals && answer.decimal))
+    }
+
+    fn saved(&self) -> Option<SavedQuestion> {
+        Some(SavedQuestion::Fraction(self.clone()))
+    }
+}
+
+/// The value of a whole number token, e.g. the `3` in `3/4`
+fn whole_number(token: &Token) -> Option<i64> {
+    match token {
+        Token::Number {
+            value,
+            is_integer: true,
+            ..
+        } => Some(value.numerator()),
+        _ => None,
+    }
+}
+
+/// A fraction written as `top/bottom`, or a mixed number if there's a whole part before it
+fn written_answer(
+    whole: Option<&Token>,
+    top: &Token,
+    bottom: &Token,
+) -> Result<FractionAnswer, AnswerParseError> {
+    let (Some(top), Some(bottom)) = (whole_number(top), whole_number(bottom)) else {
+        return Err(AnswerParseError::NotAFraction);
+    };
+    if bottom == 0 {
+        return Err(AnswerParseError::DivideByZero);
+    }
+    let fraction = Rational::new(top, bottom);
+    let lowest_terms = fraction.denominator() == bottom;
+    match whole {
+        None => Ok(FractionAnswer {
+            value: fraction,
+            lowest_terms,
+            decimal: false,
+        }),
+        Some(whole) => {
+            let whole = whole_number(whole).ok_or(AnswerParseError::NotAFraction)?;
+            Ok(FractionAnswer {
+                value: Rational::from(whole)
+                    .checked_add(fraction)
+                    .ok_or(AnswerParseError::TooLarge)?,
+                // the fraction part of a mixed number should be less than one as well
+                lowest_terms: lowest_terms && top < bottom,
+                decimal: false,
+            })
+        }
+    }
+}
+
+impl Answer for FractionAnswer {
+    /// Reads a single whole number, fraction, mixed number or decimal, with a sign in front if
+    /// it's negative. Anything worked out like `4 ÷ 20 × 1` isn't an answer
+    fn parse(text: &str) -> Result<Self, AnswerParseError> {
+        let tokens = tokenize(text)?;
+        let (negative, unsigned) = match tokens.as_slice() {
+            [] => return Err(AnswerParseError::Empty),
+            [Token::Subtract, rest @ ..] => (true, rest),
+            tokens => (false, tokens),
+        };
+        let answer = match unsigned {
+            [
+                Token::Number {
+                    value, is_integer, ..
+                },
+            ] => FractionAnswer {
+                value: *value,
+                lowest_terms: *is_integer,
+                decimal: !*is_integer,
+            },
+            [top, Token::Divide, bottom] => written_answer(None, top, bottom)?,
+            [
+                whole,
+                top @ Token::Number { spaced: true, .. },
+                Token::Divide,
+                bottom,
+            ] => written_answer(Some(whole), top, bottom)?,
+            [] => return Err(AnswerParseError::UnexpectedEnd),
+            _ => return Err(AnswerParseError::NotAFraction),
+        };
+        if !negative {
+            return Ok(answer);
+        }
+        Ok(FractionAnswer {
+            value: answer
+                .value
+                .checked_neg()
+                .ok_or(AnswerParseError::TooLarge)?,
+            ..answer
+        })
+    }
+
+    fn hint() -> &'static str {
+        "Fraction in lowest terms, e.g. 3/4"
+    }
+}
+
+impl Display for FractionAnswer {
+    /// Always as a fraction, since [`Rational`] writes terminating values as decimals
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.value.is_integer() {
+            write!(f, "{}", self.value.numerator())
+        } else {
+            write!(f, "{}/{}", self.value.numerator(), self.value.denominator())
+        }
+    }
+}
+
+impl MathLayout for WrittenFraction {
+    fn math_layout(&self) -> MathBox {
+        MathBox::Fraction(
+            Box::new(MathBox::text(self.numerator.to_string())),
+            Box::new(MathBox::text(self.denominator.to_string())),
+        )
+    }
+}
+
+impl MathLayout for FractionEquation {
+    fn math_layout(&self) -> MathBox {
+        match self.question {
+            FractionQuestion::Operation(left, operator, right) => MathBox::Row(vec![
+                left.math_layout(),
+                MathBox::text(operator.to_string()),
+                right.math_layout(),
+            ]),
+            FractionQuestion::Simplify(fraction) => fraction.math_layout(),
+        }
+    }
+}
+
+impl Display for WrittenFraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.numerator, self.denominator)
+    }
+}
+
+impl Display for FractionOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            FractionOperator::Add => "+",
+            FractionOperator::Subtract => "-",
+            FractionOperator::Multiply => "×",
+        };
+        write!(f, "{}", string)
+    }
+}
+
+impl Display for FractionEquation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.question {
+            FractionQuestion::Operation(left, operator, right) => {
+                write!(f, "{} {} {}", left, operator, right)
+            }
+            FractionQuestion::Simplify(fraction) => write!(f, "Simplify {}", fraction),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::equation::Question;
+
+    fn fraction(numerator: i64, denominator: i64) -> WrittenFraction {
+        WrittenFraction {
+            numerator,
+            denominator,
+        }
+    }
+
+    #[test]
+    fn requires_lowest_terms() {
+        let equation = FractionEquation {
+            question: FractionQuestion::Operation(
+                fraction(1, 4),
+                FractionOperator::Add,
+                fraction(1, 4),
+            ),
+            accept_decimals: false,
+        };
+        assert_eq!(equation.answer_text(), "1/2");
+        assert_eq!(equation.to_latex(), "\\frac{1}{4} + \\frac{1}{4}");
+        assert_eq!(equation.check_answer("1/2"), Ok(true));
+        assert_eq!(equation.check_answer("2/4"), Ok(false));
+        assert_eq!(equation.check_answer("0.5"), Ok(false));
+
+        let equation = FractionEquation {
+            accept_decimals: true,
+            ..equation
+        };
+        assert_eq!(equation.check_answer("0.5"), Ok(true));
+        assert_eq!(equation.check_answer("2/4"), Ok(false));
+    }
+
+    #[test]
+    fn checks_mixed_numbers_and_simplifying() {
+        let equation = FractionEquation {
+            question: FractionQuestion::Simplify(fraction(12, 8)),
+            accept_decimals: false,
+        };
+        assert_eq!(equation.to_string(), "Simplify 12/8");
+        assert_eq!(equation.check_answer("3/2"), Ok(true));
+        assert_eq!(equation.check_answer("1 1/2"), Ok(true));
+        assert_eq!(equation.check_answer("1 2/4"), Ok(false));
+        assert_eq!(equation.check_answer("1 3/6"), Ok(false));
+        assert_eq!(equation.check_answer("1.5"), Ok(false));
+    }
+
+    #[test]
+    fn only_accepts_a_single_fraction() {
+        let equation = FractionEquation {
+            question: FractionQuestion::Simplify(fraction(4, 20)),
+            accept_decimals: true,
+        };
+        assert_eq!(equation.check_answer("1/5"), Ok(true));
+        assert_eq!(equation.check_answer("0.2"), Ok(true));
+        assert_eq!(equation.check_answer("4÷20"), Ok(false));
+        assert_eq!(
+            equation.check_answer("4/(20)"),
+            Err(AnswerParseError::NotAFraction)
+        );
+        assert_eq!(
+            equation.check_answer("2/20 + 2/20"),
+            Err(AnswerParseError::NotAFraction)
+        );
+        assert_eq!(
+            equation.check_answer("1 × 1/5"),
+            Err(AnswerParseError::NotAFraction)
+        );
+        assert_eq!(
+            equation.check_answer("1/0"),
+            Err(AnswerParseError::DivideByZero)
+        );
+
+        let equation = FractionEquation {
+            question: FractionQuestion::Operation(
+                fraction(1, 4),
+                FractionOperator::Subtract,
+                fraction(3, 4),
+            ),
+            accept_decimals: false,
+        };
+        assert_eq!(equation.check_answer("-1/2"), Ok(true));
+        assert_eq!(equation.check_answer("-2/4"), Ok(false));
+        assert_eq!(
+            equation.check_answer("1/4 - 3/4"),
+            Err(AnswerParseError::NotAFraction)
+        );
+    }
+
+    #[test]
+    fn generated_simplifications_can_be_simplified() {
+        let mut rng = StdRng::seed_from_u64(19);
+        for _ in 0..100 {
+            let equation = new_simplify_equation(&mut rng, false);
+            let FractionQuestion::Simplify(written) = equation.question else {
+                unreachable!()
+            };
+            assert!(
+                !FractionAnswer::parse(&written.to_string())
+                    .unwrap()
+                    .lowest_terms
+            );
+            assert_eq!(equation.check_answer(&equation.answer_text()), Ok(true));
+
+            let equation = new_fraction_equation(&mut rng, false);
+            assert_eq!(equation.check_answer(&equation.answer_text()), Ok(true));
+        }
+    }
+}
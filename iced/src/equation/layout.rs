@@ -0,0 +1,186 @@
+// 2D layout of equations, independent of how it ends up being drawn
+
+/// A box of an equation laid out in two dimensions, e.g. with stacked fractions
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathBox {
+    /// Numbers and operators drawn as they are
+    Text(String),
+    /// Boxes laid out left to right
+    Row(Vec<MathBox>),
+    /// The numerator stacked over the denominator
+    Fraction(Box<MathBox>, Box<MathBox>),
+    /// The base with the exponent as a superscript
+    Power(Box<MathBox>, Box<MathBox>),
+    Radical(Box<MathBox>),
+    /// A radical with its index drawn small before it, e.g. a cube root
+    Root(Box<MathBox>, Box<MathBox>),
+    /// Brackets sized to fit around the contents
+    Brackets(Box<MathBox>),
+    Absolute(Box<MathBox>),
+}
+
+impl MathBox {
+    pub fn text(text: impl Into<String>) -> Self {
+        MathBox::Text(text.into())
+    }
+
+    /// Whether the first thing drawn is a minus sign
+    pub fn starts_with_minus(&self) -> bool {
+        match self {
+            MathBox::Text(text) => text.starts_with('-'),
+            MathBox::Row(boxes) => boxes.first().is_some_and(MathBox::starts_with_minus),
+            MathBox::Power(base, _) => base.starts_with_minus(),
+            _ => false,
+        }
+    }
+}
+
+impl MathBox {
+    /// Writes the box as presentation MathML, without the surrounding `<math>` element
+    pub fn to_mathml(&self) -> String {
+        match self {
+            MathBox::Text(text) => text_to_mathml(text),
+            MathBox::Row(boxes) => format!(
+                "<mrow>{}</mrow>",
+                boxes.iter().map(MathBox::to_mathml).collect::<String>()
+            ),
+            MathBox::Fraction(numerator, denominator) => format!(
+                "<mfrac>{}{}</mfrac>",
+                numerator.to_mathml(),
+                denominator.to_mathml()
+            ),
+            MathBox::Power(base, exponent) => {
+                format!("<msup>{}{}</msup>", base.to_mathml(), exponent.to_mathml())
+            }
+            MathBox::Radical(inner) => format!("<msqrt>{}</msqrt>", inner.to_mathml()),
+            MathBox::Root(index, inner) => {
+                format!("<mroot>{}{}</mroot>", inner.to_mathml(), index.to_mathml())
+            }
+            MathBox::Brackets(inner) => {
+                format!("<mrow><mo>(</mo>{}<mo>)</mo></mrow>", inner.to_mathml())
+            }
+            MathBox::Absolute(inner) => {
+                format!("<mrow><mo>|</mo>{}<mo>|</mo></mrow>", inner.to_mathml())
+            }
+        }
+    }
+}
+
+impl MathBox {
+    /// Writes the box as LaTeX maths, without surrounding `$`s
+    pub fn to_latex(&self) -> String {
+        match self {
+            MathBox::Text(text) => match text.as_str() {
+                "×" => "\\times".to_string(),
+                "÷" => "\\div".to_string(),
+                "−" => "-".to_string(),
+                "mod" => "\\bmod".to_string(),
+                _ => text.clone(),
+            },
+            MathBox::Row(boxes) => boxes
+                .iter()
+                .map(MathBox::to_latex)
+                .collect::<Vec<_>>()
+                .join(" "),
+            MathBox::Fraction(numerator, denominator) => format!(
+                "\\frac{{{}}}{{{}}}",
+                numerator.to_latex(),
+                denominator.to_latex()
+            ),
+            MathBox::Power(base, exponent) => {
+                format!("{{{}}}^{{{}}}", base.to_latex(), exponent.to_latex())
+            }
+            MathBox::Radical(inner) => format!("\\sqrt{{{}}}", inner.to_latex()),
+            MathBox::Root(index, inner) => {
+                format!("\\sqrt[{}]{{{}}}", index.to_latex(), inner.to_latex())
+            }
+            MathBox::Brackets(inner) => format!("\\left( {} \\right)", inner.to_latex()),
+            MathBox::Absolute(inner) => format!("\\left| {} \\right|", inner.to_latex()),
+        }
+    }
+}
+
+/// Numbers become `<mn>`, single letters `<mi>`, a number before a letter like `3x` a product,
+/// and anything else, including words like `mod`, an operator `<mo>`
+fn text_to_mathml(text: &str) -> String {
+    let is_number =
+        |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit() || c == '.');
+    if is_number(text) {
+        format!("<mn>{}</mn>", text)
+    } else if let Some(number) = text.strip_prefix('-')
+        && is_number(number)
+    {
+        format!("<mrow><mo>-</mo><mn>{}</mn></mrow>", number)
+    } else if text.chars().count() == 1 && text.chars().all(char::is_alphabetic) {
+        format!("<mi>{}</mi>", text)
+    } else if let Some(letter) = text.chars().last()
+        && letter.is_alphabetic()
+        && let coefficient = &text[..text.len() - letter.len_utf8()]
+        && (coefficient == "-" || is_number(coefficient.trim_start_matches('-')))
+    {
+        format!(
+            "<mrow>{}<mo>&#x2062;</mo><mi>{}</mi></mrow>",
+            text_to_mathml(coefficient),
+            letter
+        )
+    } else {
+        let escaped = text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+        format!("<mo>{}</mo>", escaped)
+    }
+}
+
+/// Something which can be laid out as a [`MathBox`] to be drawn
+pub trait MathLayout {
+    fn math_layout(&self) -> MathBox;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MathBox;
+
+    #[test]
+    fn writes_mathml() {
+        let math_box = MathBox::Row(vec![
+            MathBox::Fraction(
+                Box::new(MathBox::text("1.5")),
+                Box::new(MathBox::text("-2")),
+            ),
+            MathBox::text("×"),
+            MathBox::Power(
+                Box::new(MathBox::Brackets(Box::new(MathBox::Radical(Box::new(
+                    MathBox::text("4"),
+                ))))),
+                Box::new(MathBox::text("2")),
+            ),
+        ]);
+        assert_eq!(
+            math_box.to_mathml(),
+            "<mrow><mfrac><mn>1.5</mn><mrow><mo>-</mo><mn>2</mn></mrow></mfrac><mo>×</mo>\
+             <msup><mrow><mo>(</mo><msqrt><mn>4</mn></msqrt><mo>)</mo></mrow><mn>2</mn></msup></mrow>"
+        );
+    }
+
+    #[test]
+    fn writes_latex() {
+        let math_box = MathBox::Row(vec![
+            MathBox::Fraction(
+                Box::new(MathBox::text("1.5")),
+                Box::new(MathBox::text("-2")),
+            ),
+            MathBox::text("×"),
+            MathBox::Power(
+                Box::new(MathBox::Brackets(Box::new(MathBox::Radical(Box::new(
+                    MathBox::text("4"),
+                ))))),
+                Box::new(MathBox::text("2")),
+            ),
+        ]);
+        assert_eq!(
+            math_box.to_latex(),
+            "\\frac{1.5}{-2} \\times {\\left( \\sqrt{4} \\right)}^{2}"
+        );
+    }
+}
@@ -1,124 +1,614 @@
 use rand::{
+    Rng,
     distr::{Distribution, StandardUniform},
     seq::IndexedRandom,
 };
+use serde::{Deserialize, Serialize};
 
-use super::Equation;
+use super::{
+    Evaluate,
+    answer::{AnswerParseError, SUPERSCRIPT_DIGITS, Token, tokenize},
+    config::GeneratorConfig,
+    layout::{MathBox, MathLayout},
+    rational::Rational,
+    saved::SavedQuestion,
+};
 use std::fmt::Display;
 
-pub fn new_medium_equation() -> EquationValue {
-    let depth = rand::random_range(1..2);
+/// Generates an equation following the config, every difficulty goes through here
+pub fn new_configured_equation<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+) -> EquationValue {
+    let config = config.validated();
+    let depth = rng.random_range(config.min_depth..=config.max_depth);
 
-    let equation_node = generate_equation_backwards(depth);
+    let equation_node = generate_equation_forwards(rng, &config, depth);
     EquationValue {
         node: equation_node,
     }
 }
 
-pub fn new_complex_equation() -> EquationValue {
-    let depth = rand::random_range(2..4);
+/// A small whole number raised to a power, e.g. `3⁴`
+pub fn new_power_equation<R: Rng + ?Sized>(rng: &mut R) -> EquationValue {
+    let exponent = rng.random_range(2..=5);
+    // smaller bases for bigger exponents so the answer stays a few digits long
+    let max_base = match exponent {
+        2 => 15,
+        3 => 10,
+        4 => 5,
+        _ => 4,
+    };
+    let operator = match exponent {
+        2 => SingleOperator::Square,
+        3 => SingleOperator::Cube,
+        _ => SingleOperator::Power(exponent),
+    };
+    let base = leaf_node(rng.random_range(2..=max_base));
+    EquationValue {
+        node: single_node(operator, base),
+    }
+}
 
-    let equation_node = generate_equation_backwards(depth);
+/// The square root of a perfect square up to 15² or the cube root of a perfect cube up to 10³
+pub fn new_root_equation<R: Rng + ?Sized>(rng: &mut R) -> EquationValue {
+    let (operator, radicand) = if rng.random() {
+        let root = rng.random_range(1..=15);
+        (SingleOperator::SquareRoot, root * root)
+    } else {
+        let root = rng.random_range(1..=10);
+        (SingleOperator::CubeRoot, root * root * root)
+    };
     EquationValue {
-        node: equation_node,
+        node: single_node(operator, leaf_node(radicand)),
     }
 }
 
-fn generate_equation_backwards(depth: i32) -> Node {
-    fn equation_value(depth: i32, end_value: f64) -> Node {
-        if depth == 0 {
-            Node::LeafNode(LeafNode::new(end_value))
+/// Sums of products, quotients and powers with no brackets, so they have to be worked out in
+/// the right order, e.g. `3 + 4 × 2²`
+pub fn new_order_of_operations_equation<R: Rng + ?Sized>(rng: &mut R) -> EquationValue {
+    let mut node = order_of_operations_term(rng, true);
+    for _ in 0..rng.random_range(1..=2) {
+        let operator = if rng.random() {
+            DoubleOperator::Add
         } else {
-            generate_equation_backwards_value(depth - 1, end_value)
+            DoubleOperator::Subtract
+        };
+        // the last term always binds tighter than the sum so the order matters
+        node = double_node(operator, node, order_of_operations_term(rng, false));
+    }
+    EquationValue { node }
+}
+
+/// A term of a sum with whole number operands, a plain number only if `allow_number` is set
+fn order_of_operations_term<R: Rng + ?Sized>(rng: &mut R, allow_number: bool) -> Node {
+    let square =
+        |rng: &mut R| single_node(SingleOperator::Square, leaf_node(rng.random_range(2..=5)));
+    let first = if allow_number { 0 } else { 1 };
+    match rng.random_range(first..=4) {
+        0 => leaf_node(rng.random_range(1..=12)),
+        1 => double_node(
+            DoubleOperator::Multiply,
+            leaf_node(rng.random_range(2..=12)),
+            leaf_node(rng.random_range(2..=12)),
+        ),
+        2 => {
+            let divisor = rng.random_range(2..=12);
+            let quotient = rng.random_range(1..=12);
+            double_node(
+                DoubleOperator::Divide,
+                leaf_node(divisor * quotient),
+                leaf_node(divisor),
+            )
         }
+        3 => double_node(
+            DoubleOperator::Multiply,
+            leaf_node(rng.random_range(2..=9)),
+            square(rng),
+        ),
+        _ => square(rng),
     }
+}
 
-    fn generate_equation_backwards_value(depth: i32, end_value: f64) -> Node {
-        let operator = rand::random::<DoubleOperator>();
+fn leaf_node(value: i64) -> Node {
+    Node::LeafNode(LeafNode::new(Rational::from(value)))
+}
 
-        // construct value
+fn double_node(operator: DoubleOperator, left_value: Node, right_value: Node) -> Node {
+    Node::TreeNode(TreeNode::DoubleTreeNode(DoubleTreeNode {
+        operator,
+        left_value: Box::new(left_value),
+        right_value: Box::new(right_value),
+    }))
+}
 
-        let (left_value, right_value) = match operator {
-            DoubleOperator::Add => {
-                let left_value = random_f64();
-                let right_value = end_value - left_value;
+fn single_node(operator: SingleOperator, value: Node) -> Node {
+    Node::TreeNode(TreeNode::SingleTreeNode(SingleTreeNode {
+        operator,
+        value: Box::new(value),
+    }))
+}
 
-                (left_value, right_value)
-            }
-            DoubleOperator::Subtract => {
-                // a - b = c
-                // make random a, then b = a - c
-                let left_value = random_f64();
-                let right_value = left_value - end_value;
+/// Reads an equation written out by hand like `(3 + 4) × 2²`, keeping the brackets it was
+/// written with. It has to work out to a value, so it can't divide by zero or overflow
+pub fn parse_equation(text: &str) -> Result<EquationValue, AnswerParseError> {
+    // mixed numbers aren't allowed since they'd be written back differently
+    let equation = parse_tokens(tokenize(text)?, false)?;
+    equation.checked_value()?;
+    Ok(equation)
+}
 
-                (left_value, right_value)
+/// Builds the tree for both written equations and typed answers, which are then worked out
+/// with [`EquationValue::checked_value`]. A mixed number like `1 1/2` becomes a single leaf
+pub(super) fn parse_tokens(
+    tokens: Vec<Token>,
+    mixed_numbers: bool,
+) -> Result<EquationValue, AnswerParseError> {
+    let mut parser = TreeParser {
+        tokens,
+        position: 0,
+        mixed_numbers,
+        depth: 0,
+    };
+    let node = parser.expression()?;
+    match parser.next() {
+        None => Ok(EquationValue { node }),
+        Some(Token::CloseBracket) => Err(AnswerParseError::UnbalancedBrackets),
+        Some(token) => Err(AnswerParseError::UnexpectedToken(token.to_string())),
+    }
+}
+
+/// The deepest tree the parser builds. Any deeper and parsing, working out or dropping it could
+/// overflow the stack, since it's all done recursively
+const MAX_DEPTH: usize = 100;
+
+struct TreeParser {
+    tokens: Vec<Token>,
+    position: usize,
+    mixed_numbers: bool,
+    /// How deep the node being parsed could be in the tree
+    depth: usize,
+}
+
+impl TreeParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Goes a level deeper into the tree, failing once it's deeper than [`MAX_DEPTH`]. Whoever
+    /// calls this sets the depth back once the level's parsed
+    fn deeper(&mut self) -> Result<(), AnswerParseError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(AnswerParseError::TooDeep);
+        }
+        Ok(())
+    }
+
+    // expression = term (("+" | "-") term)*
+    fn expression(&mut self) -> Result<Node, AnswerParseError> {
+        let depth = self.depth;
+        let mut node = self.term()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Add) => DoubleOperator::Add,
+                Some(Token::Subtract) => DoubleOperator::Subtract,
+                _ => break,
+            };
+            self.next();
+            // each operator in a row puts the ones before it a level deeper
+            self.deeper()?;
+            node = double_node(operator, node, self.term()?);
+        }
+        self.depth = depth;
+        Ok(node)
+    }
+
+    // term = unary (("*" | "/") unary)*
+    fn term(&mut self) -> Result<Node, AnswerParseError> {
+        let depth = self.depth;
+        let mut node = self.unary()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Multiply) => DoubleOperator::Multiply,
+                Some(Token::Divide) => DoubleOperator::Divide,
+                _ => break,
+            };
+            self.next();
+            self.deeper()?;
+            node = double_node(operator, node, self.unary()?);
+        }
+        self.depth = depth;
+        Ok(node)
+    }
+
+    // unary = ("+" | "-" | "√" | "∛") unary | postfix
+    fn unary(&mut self) -> Result<Node, AnswerParseError> {
+        let operator = match self.peek() {
+            Some(Token::Add) => None,
+            Some(Token::Subtract) => Some(SingleOperator::Negate),
+            Some(Token::SquareRoot) => Some(SingleOperator::SquareRoot),
+            Some(Token::CubeRoot) => Some(SingleOperator::CubeRoot),
+            _ => return self.postfix(),
+        };
+        self.next();
+        // a plus doesn't add a node but still recurses
+        self.deeper()?;
+        let node = self.unary()?;
+        self.depth -= 1;
+        Ok(match operator {
+            Some(operator) => single_node(operator, node),
+            None => node,
+        })
+    }
+
+    // postfix = primary power*
+    fn postfix(&mut self) -> Result<Node, AnswerParseError> {
+        let depth = self.depth;
+        let mut node = self.primary()?;
+        while let Some(&Token::Power(exponent)) = self.peek() {
+            self.next();
+            self.deeper()?;
+            let operator = match exponent {
+                2 => SingleOperator::Square,
+                3 => SingleOperator::Cube,
+                _ => SingleOperator::Power(exponent),
+            };
+            node = single_node(operator, node);
+        }
+        self.depth = depth;
+        Ok(node)
+    }
+
+    // primary = number | mixed number | "(" expression ")" | "|" expression "|"
+    fn primary(&mut self) -> Result<Node, AnswerParseError> {
+        match self.next() {
+            Some(Token::Number {
+                value, is_integer, ..
+            }) => {
+                let value = match self.mixed_fraction(is_integer)? {
+                    Some(fraction) => value
+                        .checked_add(fraction)
+                        .ok_or(AnswerParseError::TooLarge)?,
+                    None => value,
+                };
+                Ok(Node::LeafNode(LeafNode::new(value)))
             }
-            DoubleOperator::Multiply => {
-                // ab=c, we have c
-                // need factors of c to get even numbers
-                let factors = find_float_factors(end_value, 1);
-                let (left_value, right_value) = match factors.choose(&mut rand::rng()) {
-                    Some(factor_pair) => factor_pair,
-                    None => unreachable!(),
+            Some(open @ (Token::OpenBracket | Token::AbsoluteBar)) => {
+                self.deeper()?;
+                let node = self.expression()?;
+                self.depth -= 1;
+                let (close, operator) = match open {
+                    Token::OpenBracket => (Token::CloseBracket, SingleOperator::Brackets),
+                    _ => (Token::AbsoluteBar, SingleOperator::Absolute),
                 };
-
-                (*left_value, *right_value)
+                if self.next() != Some(close) {
+                    return Err(AnswerParseError::UnbalancedBrackets);
+                }
+                Ok(single_node(operator, node))
             }
-            DoubleOperator::Divide => {
-                // a / b = c
-                // need a to be a multiple of b
-                // choose small value to make it even
-                let right_value = find_divisor(end_value, 1);
-                let left_value = right_value * end_value;
+            Some(Token::CloseBracket) => Err(AnswerParseError::UnbalancedBrackets),
+            Some(token) => Err(AnswerParseError::UnexpectedToken(token.to_string())),
+            None => Err(AnswerParseError::UnexpectedEnd),
+        }
+    }
 
+    /// Reads the `1/2` of a mixed number like `3 1/2` if they're allowed and it follows a
+    /// whole number
+    fn mixed_fraction(
+        &mut self,
+        after_integer: bool,
+    ) -> Result<Option<Rational>, AnswerParseError> {
+        let is_mixed = matches!(
+            (self.peek(), self.peek_at(1), self.peek_at(2)),
+            (
+                Some(Token::Number {
+                    is_integer: true,
+                    spaced: true,
+                    ..
+                }),
+                Some(Token::Divide),
+                Some(Token::Number {
+                    is_integer: true,
+                    ..
+                })
+            )
+        );
+        if !self.mixed_numbers || !after_integer || !is_mixed {
+            return Ok(None);
+        }
+
+        let (Some(Token::Number { value: top, .. }), _, Some(Token::Number { value: bottom, .. })) =
+            (self.next(), self.next(), self.next())
+        else {
+            unreachable!()
+        };
+        if bottom.is_zero() {
+            return Err(AnswerParseError::DivideByZero);
+        }
+        top.checked_div(bottom)
+            .map(Some)
+            .ok_or(AnswerParseError::TooLarge)
+    }
+}
+
+/// Builds a tree with any answer. The top operator's operands are picked first so products
+/// and quotients aren't stuck with the factors of a random answer
+fn generate_equation_forwards<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    depth: i32,
+) -> Node {
+    if rng.random_bool(config.single_operator_chance) {
+        let answer = random_value(rng, config);
+        if let Some(node) = single_operator_node(rng, config, depth, answer) {
+            return node;
+        }
+    }
+
+    let operator = choose_operator(rng, config);
+    let (left_value, right_value) = match operator {
+        DoubleOperator::Add => (random_value(rng, config), random_value(rng, config)),
+        DoubleOperator::Subtract => {
+            let (left_value, right_value) = (random_value(rng, config), random_value(rng, config));
+            if !config.allow_negatives && left_value < right_value {
+                (right_value, left_value)
+            } else {
                 (left_value, right_value)
             }
-        };
+        }
+        DoubleOperator::Multiply => {
+            // the second factor is whole so the answer doesn't get more decimal places than the
+            // operands
+            let whole_config = GeneratorConfig {
+                decimal_places: 0,
+                ..*config
+            };
+            (random_value(rng, config), random_value(rng, &whole_config))
+        }
+        DoubleOperator::Divide => {
+            let quotient = random_value(rng, config);
+            let divisor = find_divisor(rng, config, quotient);
+            (divisor * quotient, divisor)
+        }
+    };
 
-        let left_node = equation_value(depth, left_value);
-        let right_node = equation_value(depth, right_value);
+    double_operator_node(rng, config, depth, operator, left_value, right_value)
+}
 
-        let double_tree_node = DoubleTreeNode {
-            operator,
-            left_value: Box::new(left_node),
-            right_value: Box::new(right_node),
-        };
-        Node::TreeNode(TreeNode::DoubleTreeNode(double_tree_node))
+/// Builds a tree of the given depth which evaluates exactly to `answer`
+fn generate_equation_for_value<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    depth: i32,
+    answer: Rational,
+) -> Node {
+    // sometimes use a single operator, if there's one that can reach the value
+    if rng.random_bool(config.single_operator_chance)
+        && let Some(node) = single_operator_node(rng, config, depth, answer)
+    {
+        return node;
     }
 
-    let answer = random_f64();
+    let operator = choose_operator(rng, config);
+
+    // construct value
+
+    let (left_value, right_value) = match operator {
+        DoubleOperator::Add => {
+            // a + b = c
+            // without negatives a has to be between 0 and c
+            let left_value = if config.allow_negatives {
+                random_value(rng, config)
+            } else {
+                random_value_between(rng, config, Rational::ZERO, answer)
+            };
+            let right_value = answer - left_value;
+
+            (left_value, right_value)
+        }
+        DoubleOperator::Subtract => {
+            // a - b = c
+            // make random a, then b = a - c
+            // without negatives a has to be at least c
+            let left_value = if config.allow_negatives {
+                random_value(rng, config)
+            } else {
+                answer + random_value(rng, config)
+            };
+            let right_value = left_value - answer;
+
+            (left_value, right_value)
+        }
+        DoubleOperator::Multiply => {
+            // ab=c, we have c
+            // need factors of c to get even numbers
+            let factors = find_factors(rng, config, answer);
+            let (left_value, right_value) = match factors.choose(rng) {
+                Some(factor_pair) => factor_pair,
+                None => unreachable!(),
+            };
 
-    generate_equation_backwards_value(depth, answer)
+            (*left_value, *right_value)
+        }
+        DoubleOperator::Divide => {
+            // a / b = c
+            // need a to be a multiple of b
+            // choose small value to make it even
+            let right_value = find_divisor(rng, config, answer);
+            let left_value = right_value * answer;
+
+            (left_value, right_value)
+        }
+    };
+
+    double_operator_node(rng, config, depth, operator, left_value, right_value)
+}
+
+/// An operand which evaluates to `value`, a leaf if the tree is deep enough already
+fn operand_node<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    depth: i32,
+    value: Rational,
+) -> Node {
+    if depth == 0 {
+        Node::LeafNode(LeafNode::new(value))
+    } else {
+        generate_equation_for_value(rng, config, depth - 1, value)
+    }
 }
 
-fn find_float_factors(num: f64, precision: u32) -> Vec<(f64, f64)> {
-    //
-    let positive = match num.signum() {
-        1.0 => true,
-        -1.0 => false,
-        _ => unreachable!(),
+fn double_operator_node<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    depth: i32,
+    operator: DoubleOperator,
+    left_value: Rational,
+    right_value: Rational,
+) -> Node {
+    let left_node = operand_node(rng, config, depth, left_value);
+    let right_node = operand_node(rng, config, depth, right_value);
+
+    let double_tree_node = DoubleTreeNode {
+        operator,
+        left_value: Box::new(left_node),
+        right_value: Box::new(right_node),
+    };
+    Node::TreeNode(TreeNode::DoubleTreeNode(double_tree_node))
+}
+
+/// A single operator applied to something which gives `end_value`, if there's an operator
+/// which can reach it
+fn single_operator_node<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    depth: i32,
+    end_value: Rational,
+) -> Option<Node> {
+    let operators: Vec<(SingleOperator, Rational)> = GENERATED_SINGLE_OPERATORS
+        .into_iter()
+        .filter_map(|operator| {
+            find_single_operand(rng, config, operator, end_value).map(|value| (operator, value))
+        })
+        .collect();
+    let (operator, value) = operators.choose(rng)?;
+    let single_tree_node = SingleTreeNode {
+        operator: *operator,
+        value: Box::new(operand_node(rng, config, depth, *value)),
     };
-    let precision_f64 = f64::from(10i32.pow(precision));
-    // convert number to absolute number with precision, e.g. -16.4 with precision 1 -> 164
-    let num_with_precision = num.abs() * precision_f64;
+    Some(Node::TreeNode(TreeNode::SingleTreeNode(single_tree_node)))
+}
+
+/// Picks a double operator using the weights in the config, or any of them if they're all zero
+fn choose_operator<R: Rng + ?Sized>(rng: &mut R, config: &GeneratorConfig) -> DoubleOperator {
+    let weights = config.operator_weights;
+    let operators = [
+        (DoubleOperator::Add, weights.add),
+        (DoubleOperator::Subtract, weights.subtract),
+        (DoubleOperator::Multiply, weights.multiply),
+        (DoubleOperator::Divide, weights.divide),
+    ];
+    match operators.choose_weighted(rng, |(_, weight)| *weight) {
+        Ok((operator, _)) => *operator,
+        Err(_) => rng.random(),
+    }
+}
+
+/// The single operators which are used when generating equations
+const GENERATED_SINGLE_OPERATORS: [SingleOperator; 5] = [
+    SingleOperator::Square,
+    SingleOperator::SquareRoot,
+    SingleOperator::Negate,
+    SingleOperator::Absolute,
+    SingleOperator::Cube,
+];
+
+/// Finds a value which gives `end_value` when the operator is applied to it, if there is one
+fn find_single_operand<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    operator: SingleOperator,
+    end_value: Rational,
+) -> Option<Rational> {
+    let random_sign = |rng: &mut R, value: Rational| {
+        if !config.allow_negatives || rng.random() {
+            value
+        } else {
+            -value
+        }
+    };
+    match operator {
+        SingleOperator::Brackets => None,
+        // x² = c, c needs to be a perfect square
+        SingleOperator::Square => end_value.nth_root(2).map(|root| random_sign(rng, root)),
+        // √x = c, keep c small so x doesn't get too big
+        SingleOperator::SquareRoot => (end_value >= Rational::ZERO
+            && end_value <= Rational::from(50))
+        .then(|| end_value * end_value),
+        // both of these only make sense with negative numbers around
+        SingleOperator::Negate => config.allow_negatives.then_some(-end_value),
+        SingleOperator::Absolute => (config.allow_negatives && end_value >= Rational::ZERO)
+            .then(|| random_sign(rng, end_value)),
+        // x³ = c, c needs to be a perfect cube
+        SingleOperator::Cube => end_value.nth_root(3),
+        // only used by the power and root questions
+        SingleOperator::CubeRoot | SingleOperator::Power(_) => None,
+    }
+}
+
+/// Finds pairs of decimals which multiply exactly to `num`, splitting its decimal places
+/// between the pair, e.g. 16.4 -> (4, 4.1), (0.2, -82) ...
+fn find_factors<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    num: Rational,
+) -> Vec<(Rational, Rational)> {
+    if num.is_zero() {
+        // anything times zero is zero
+        return vec![
+            (Rational::ZERO, random_value(rng, config)),
+            (random_value(rng, config), Rational::ZERO),
+        ];
+    }
+
+    let precision = num
+        .decimal_places()
+        .expect("generated values should be terminating decimals");
+    let precision_value = 10i64.pow(precision);
+    // convert number to absolute integer with precision, e.g. -16.4 with precision 1 -> 164
+    let num_with_precision = (num.abs() * Rational::from(precision_value)).numerator();
     // the highest number we need to check for factors is the square root
-    let max_num = num_with_precision.sqrt() as i32 + 1;
-    let num_with_precision = num_with_precision.round() as i32;
+    let max_num = num_with_precision.isqrt() + 1;
     let mut factors = vec![];
     for value in 1..max_num {
         // check if it's a factor
         if num_with_precision % value == 0 {
             let factor_1 = value;
             let factor_2 = num_with_precision / value;
-            let first = rand::random();
-            let factor_1_precision = if first { f64::from(factor_1) / precision_f64 } else { f64::from(factor_1) };
-            let factor_2_precision = if !first { f64::from(factor_2) / precision_f64 } else { f64::from(factor_2) };
-
-            let (sign_1, sign_2) = match (positive, rand::random()) {
-                (true, true) => (1.0, 1.0),
-                (true, false) => (-1.0, -1.0),
-                (false, true) => (-1.0, 1.0),
-                (false, false) => (1.0, -1.0),
+            // give the first factor some of the decimal places and the second the rest
+            let factor_1_places = rng.random_range(0..=precision);
+            let factor_1_precision = Rational::from_decimal(factor_1, factor_1_places);
+            let factor_2_precision = Rational::from_decimal(factor_2, precision - factor_1_places);
+
+            let (sign_1, sign_2) = match (
+                num > Rational::ZERO,
+                !config.allow_negatives || rng.random(),
+            ) {
+                (true, true) => (Rational::ONE, Rational::ONE),
+                (true, false) => (-Rational::ONE, -Rational::ONE),
+                (false, true) => (-Rational::ONE, Rational::ONE),
+                (false, false) => (Rational::ONE, -Rational::ONE),
             };
             factors.push((factor_1_precision * sign_1, factor_2_precision * sign_2));
         }
@@ -129,23 +619,32 @@ fn find_float_factors(num: f64, precision: u32) -> Vec<(f64, f64)> {
 
 // unused, old version of equation
 #[allow(dead_code)]
-fn generate_equation(depth: i32) -> Node {
-    if rand::random_ratio(1, 6) {
+fn generate_equation<R: Rng + ?Sized>(rng: &mut R, depth: i32) -> Node {
+    if rng.random_ratio(1, 6) {
         //squared
-        Node::LeafNode(LeafNode::new(random_f64()))
+        Node::LeafNode(LeafNode::new(random_value(
+            rng,
+            &GeneratorConfig::default(),
+        )))
     } else {
-        let operator = rand::random::<DoubleOperator>();
+        let operator = rng.random::<DoubleOperator>();
 
-        let left_child = if depth == 0 || rand::random() {
-            Node::LeafNode(LeafNode::new(random_f64()))
+        let left_child = if depth == 0 || rng.random() {
+            Node::LeafNode(LeafNode::new(random_value(
+                rng,
+                &GeneratorConfig::default(),
+            )))
         } else {
-            generate_equation(depth - 1)
+            generate_equation(rng, depth - 1)
         };
 
         let right_child = if depth > 0 {
-            Node::LeafNode(LeafNode::new(random_f64()))
+            Node::LeafNode(LeafNode::new(random_value(
+                rng,
+                &GeneratorConfig::default(),
+            )))
         } else {
-            generate_equation(depth - 1)
+            generate_equation(rng, depth - 1)
         };
 
         let double_tree_node = DoubleTreeNode {
@@ -157,24 +656,58 @@ fn generate_equation(depth: i32) -> Node {
     }
 }
 
-fn random_f64() -> f64 {
-    f64::from(rand::random_range(0..1000) - 500) * 0.1
+/// A random value in the config's operand range, e.g. between -50.0 and 50.0 with one
+/// decimal place
+fn random_value<R: Rng + ?Sized>(rng: &mut R, config: &GeneratorConfig) -> Rational {
+    let scale = 10i64.pow(config.decimal_places);
+    Rational::from_decimal(
+        rng.random_range(config.min_operand * scale..=config.max_operand * scale),
+        config.decimal_places,
+    )
 }
 
-fn find_divisor(end_value: f64, precision: u32) -> f64 {
-    let mut all_divisors = Vec::new();
-    let precision_f64 = f64::from(10i32.pow(precision)); 
-    let end_value = (end_value * precision_f64) as i32;
-    for value in 1..500 {
-        if (value * end_value) % 10 == 0 {
-            all_divisors.push(value);
-        } 
+/// A random value between `low` and `high` with the config's decimal places, or `low` if
+/// there isn't one
+fn random_value_between<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    low: Rational,
+    high: Rational,
+) -> Rational {
+    let scale = Rational::from(10i64.pow(config.decimal_places));
+    let low_scaled = -(-low * scale).floor();
+    let high_scaled = (high * scale).floor();
+    if low_scaled > high_scaled {
+        return low;
     }
-    let divisor_value = all_divisors.choose(&mut rand::rng()).unwrap();
-    (*divisor_value as f64) / precision_f64 
+    Rational::from_decimal(
+        rng.random_range(low_scaled..=high_scaled),
+        config.decimal_places,
+    )
 }
 
-#[derive(Debug)]
+/// Finds a positive divisor which doesn't add any decimal places when multiplied by `end_value`
+fn find_divisor<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &GeneratorConfig,
+    end_value: Rational,
+) -> Rational {
+    let max_places = end_value
+        .decimal_places()
+        .unwrap_or(0)
+        .max(config.decimal_places);
+    let largest_operand = config.max_operand.max(config.min_operand.abs()).max(1);
+    let max_divisor = largest_operand * 10i64.pow(config.decimal_places);
+    // a divisor k / 10^places works when k × end_value × 10^(max_places - places) is whole, so
+    // the working divisors are the multiples of that product's denominator. One always works
+    let extra_places = Rational::from(10i64.pow(max_places - config.decimal_places));
+    let step = (end_value * extra_places).denominator();
+    let multiples = u32::try_from(max_divisor / step).unwrap_or(u32::MAX);
+    let multiple = rng.random_range(1..=multiples);
+    Rational::from_decimal(step * i64::from(multiple), config.decimal_places)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct EquationValue {
     node: Node,
 }
@@ -185,21 +718,65 @@ impl Display for EquationValue {
     }
 }
 
-impl Equation for EquationValue {
-    fn calc_value(&self) -> f64 {
-        match &self.node {
-            Node::LeafNode(leaf_node) => leaf_node.calc_value(),
-            Node::TreeNode(tree_node) => tree_node.calc_value(),
-        }
+impl MathLayout for EquationValue {
+    fn math_layout(&self) -> MathBox {
+        self.node.math_layout()
+    }
+}
+
+impl EquationValue {
+    /// Works the equation out without panicking, for trees which weren't generated and might
+    /// divide by zero, take a root that isn't exact or overflow
+    pub fn checked_value(&self) -> Result<Rational, AnswerParseError> {
+        self.node.checked_value()
     }
 }
 
-#[derive(Debug)]
+impl Evaluate for EquationValue {
+    fn calc_value(&self) -> Rational {
+        self.node.calc_value()
+    }
+
+    fn saved(&self) -> Option<SavedQuestion> {
+        Some(SavedQuestion::Expression(self.clone()))
+    }
+}
+
+/// Saved tagged with `"node": "leaf"` or `"node": "tree"` so the JSON reads like the tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "node")]
 enum Node {
+    #[serde(rename = "leaf")]
     LeafNode(LeafNode),
+    #[serde(rename = "tree")]
     TreeNode(TreeNode),
 }
 
+impl Node {
+    fn precedence(&self) -> Precedence {
+        match self {
+            // negative numbers read like a negation
+            Node::LeafNode(leaf_node) if leaf_node.value < Rational::ZERO => Precedence::Prefix,
+            Node::LeafNode(_) => Precedence::Atom,
+            Node::TreeNode(TreeNode::DoubleTreeNode(double_tree_node)) => {
+                double_tree_node.operator.precedence()
+            }
+            Node::TreeNode(TreeNode::SingleTreeNode(single_tree_node)) => {
+                single_tree_node.operator.precedence()
+            }
+        }
+    }
+}
+
+impl MathLayout for Node {
+    fn math_layout(&self) -> MathBox {
+        match self {
+            Node::LeafNode(leaf_node) => leaf_node.math_layout(),
+            Node::TreeNode(tree_node) => tree_node.math_layout(),
+        }
+    }
+}
+
 impl Display for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -209,42 +786,110 @@ impl Display for Node {
     }
 }
 
-impl Equation for Node {
-    fn calc_value(&self) -> f64 {
+/// How tightly an operator binds its operands, from loosest to tightest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Sum,
+    Product,
+    Prefix,
+    Postfix,
+    Atom,
+}
+
+/// Lays out an operand, adding brackets in the same places as [`write_operand`] except
+/// around divisions since they're drawn as fractions
+fn operand_layout(node: &Node, min_precedence: Precedence, allow_leading_minus: bool) -> MathBox {
+    let precedence = match node {
+        Node::TreeNode(TreeNode::DoubleTreeNode(DoubleTreeNode {
+            operator: DoubleOperator::Divide,
+            ..
+        })) => Precedence::Atom,
+        _ => node.precedence(),
+    };
+    let math_box = node.math_layout();
+    if precedence < min_precedence || (!allow_leading_minus && math_box.starts_with_minus()) {
+        MathBox::Brackets(Box::new(math_box))
+    } else {
+        math_box
+    }
+}
+
+/// Lays out the base of a power, which also needs brackets around fractions
+fn power_layout(node: &Node, exponent: &str) -> MathBox {
+    let base = match operand_layout(node, Precedence::Atom, false) {
+        fraction @ MathBox::Fraction(..) => MathBox::Brackets(Box::new(fraction)),
+        base => base,
+    };
+    MathBox::Power(Box::new(base), Box::new(MathBox::text(exponent)))
+}
+
+/// Writes an operand, only adding brackets if it binds looser than `min_precedence` or it
+/// starts with a minus sign where that would be confusing, e.g. `2 - (-3)`
+fn write_operand(
+    f: &mut std::fmt::Formatter<'_>,
+    node: &Node,
+    min_precedence: Precedence,
+    allow_leading_minus: bool,
+) -> std::fmt::Result {
+    let operand = node.to_string();
+    if node.precedence() < min_precedence || (!allow_leading_minus && operand.starts_with('-')) {
+        write!(f, "({})", operand)
+    } else {
+        write!(f, "{}", operand)
+    }
+}
+
+impl Node {
+    fn checked_value(&self) -> Result<Rational, AnswerParseError> {
         match self {
-            Node::LeafNode(leaf_node) => leaf_node.calc_value(),
-            Node::TreeNode(tree_node) => tree_node.calc_value(),
+            Node::LeafNode(leaf_node) => Ok(leaf_node.value),
+            Node::TreeNode(TreeNode::DoubleTreeNode(double_tree_node)) => {
+                double_tree_node.checked_value()
+            }
+            Node::TreeNode(TreeNode::SingleTreeNode(single_tree_node)) => {
+                single_tree_node.checked_value()
+            }
         }
     }
 }
 
-#[derive(Debug)]
+impl Evaluate for Node {
+    fn calc_value(&self) -> Rational {
+        self.checked_value()
+            .expect("equations should be checked when they're read or loaded")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct LeafNode {
-    value: f64,
+    value: Rational,
 }
 
 impl LeafNode {
-    fn new(value: f64) -> Self {
+    fn new(value: Rational) -> Self {
         LeafNode { value }
     }
 }
 
 impl Display for LeafNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:.1}", self.value)
+        write!(f, "{}", self.value)
     }
 }
 
-impl Equation for LeafNode {
-    fn calc_value(&self) -> f64 {
-        self.value
+impl MathLayout for LeafNode {
+    fn math_layout(&self) -> MathBox {
+        MathBox::text(self.value.to_string())
     }
 }
 
-#[allow(dead_code)]
-#[derive(Debug)]
+/// Tagged with `"tree"` alongside the [`Node`] tag, so both end up in the same object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "tree")]
 enum TreeNode {
+    #[serde(rename = "double")]
     DoubleTreeNode(DoubleTreeNode),
+    #[serde(rename = "single")]
     SingleTreeNode(SingleTreeNode),
 }
 
@@ -257,16 +902,16 @@ impl Display for TreeNode {
     }
 }
 
-impl Equation for TreeNode {
-    fn calc_value(&self) -> f64 {
+impl MathLayout for TreeNode {
+    fn math_layout(&self) -> MathBox {
         match self {
-            TreeNode::DoubleTreeNode(double_tree_node) => double_tree_node.calc_value(),
-            TreeNode::SingleTreeNode(single_tree_node) => single_tree_node.calc_value(),
+            TreeNode::DoubleTreeNode(double_tree_node) => double_tree_node.math_layout(),
+            TreeNode::SingleTreeNode(single_tree_node) => single_tree_node.math_layout(),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct DoubleTreeNode {
     operator: DoubleOperator,
     left_value: Box<Node>,
@@ -275,56 +920,141 @@ struct DoubleTreeNode {
 
 impl Display for DoubleTreeNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.operator {
-            DoubleOperator::Add => write!(f, "({})+({})", self.left_value, self.right_value),
-            DoubleOperator::Subtract => write!(f, "({})-({})", self.left_value, self.right_value),
-            DoubleOperator::Multiply => write!(f, "({})×({})", self.left_value, self.right_value),
-            DoubleOperator::Divide => write!(f, "({})÷({})", self.left_value, self.right_value),
-        }
+        // everything is left associative, so the right operand needs brackets for the same
+        // precedence unless the operator doesn't care about grouping (a + (b - c) = a + b - c)
+        let (symbol, right_precedence) = match self.operator {
+            DoubleOperator::Add => ("+", Precedence::Sum),
+            DoubleOperator::Subtract => ("-", Precedence::Product),
+            DoubleOperator::Multiply => ("×", Precedence::Product),
+            DoubleOperator::Divide => ("÷", Precedence::Prefix),
+        };
+        write_operand(f, &self.left_value, self.operator.precedence(), true)?;
+        write!(f, " {} ", symbol)?;
+        write_operand(f, &self.right_value, right_precedence, false)
     }
 }
 
-impl Equation for DoubleTreeNode {
-    fn calc_value(&self) -> f64 {
-        let left_value = self.left_value.calc_value();
-        let right_value = self.right_value.calc_value();
-        match self.operator {
-            DoubleOperator::Add => left_value + right_value,
-            DoubleOperator::Subtract => left_value - right_value,
-            DoubleOperator::Multiply => left_value * right_value,
-            DoubleOperator::Divide => left_value / right_value,
-        }
+impl MathLayout for DoubleTreeNode {
+    fn math_layout(&self) -> MathBox {
+        let (symbol, right_precedence) = match self.operator {
+            DoubleOperator::Add => ("+", Precedence::Sum),
+            DoubleOperator::Subtract => ("-", Precedence::Product),
+            DoubleOperator::Multiply => ("×", Precedence::Product),
+            DoubleOperator::Divide => {
+                return MathBox::Fraction(
+                    Box::new(self.left_value.math_layout()),
+                    Box::new(self.right_value.math_layout()),
+                );
+            }
+        };
+        MathBox::Row(vec![
+            operand_layout(&self.left_value, self.operator.precedence(), true),
+            MathBox::text(symbol),
+            operand_layout(&self.right_value, right_precedence, false),
+        ])
+    }
+}
+
+impl DoubleTreeNode {
+    fn checked_value(&self) -> Result<Rational, AnswerParseError> {
+        let left_value = self.left_value.checked_value()?;
+        let right_value = self.right_value.checked_value()?;
+        let value = match self.operator {
+            DoubleOperator::Add => left_value.checked_add(right_value),
+            DoubleOperator::Subtract => left_value.checked_sub(right_value),
+            DoubleOperator::Multiply => left_value.checked_mul(right_value),
+            DoubleOperator::Divide if right_value.is_zero() => {
+                return Err(AnswerParseError::DivideByZero);
+            }
+            DoubleOperator::Divide => left_value.checked_div(right_value),
+        };
+        value.ok_or(AnswerParseError::TooLarge)
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 struct SingleTreeNode {
     operator: SingleOperator,
-    value: Box<TreeNode>,
+    value: Box<Node>,
 }
 
 impl Display for SingleTreeNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self.operator {
             SingleOperator::Brackets => write!(f, "({})", self.value),
-            SingleOperator::Square => write!(f, "{}²", self.value),
+            SingleOperator::Square => {
+                write_operand(f, &self.value, Precedence::Atom, false)?;
+                write!(f, "²")
+            }
+            SingleOperator::SquareRoot => {
+                write!(f, "√")?;
+                write_operand(f, &self.value, Precedence::Atom, false)
+            }
+            SingleOperator::Negate => {
+                write!(f, "-")?;
+                write_operand(f, &self.value, Precedence::Postfix, false)
+            }
+            SingleOperator::Absolute => write!(f, "|{}|", self.value),
+            SingleOperator::Cube => {
+                write_operand(f, &self.value, Precedence::Atom, false)?;
+                write!(f, "³")
+            }
+            SingleOperator::CubeRoot => {
+                write!(f, "∛")?;
+                write_operand(f, &self.value, Precedence::Atom, false)
+            }
+            SingleOperator::Power(exponent) => {
+                write_operand(f, &self.value, Precedence::Atom, false)?;
+                write!(f, "{}", superscript(exponent))
+            }
         }
     }
 }
 
-impl Equation for SingleTreeNode {
-    fn calc_value(&self) -> f64 {
+impl MathLayout for SingleTreeNode {
+    fn math_layout(&self) -> MathBox {
         match self.operator {
-            SingleOperator::Brackets => self.value.calc_value(),
-            SingleOperator::Square => {
-                let sub_value = self.value.calc_value();
-                sub_value * sub_value
-            }
+            SingleOperator::Brackets => MathBox::Brackets(Box::new(self.value.math_layout())),
+            SingleOperator::Square => power_layout(&self.value, "2"),
+            SingleOperator::SquareRoot => MathBox::Radical(Box::new(self.value.math_layout())),
+            SingleOperator::Negate => MathBox::Row(vec![
+                MathBox::text("-"),
+                operand_layout(&self.value, Precedence::Postfix, false),
+            ]),
+            SingleOperator::Absolute => MathBox::Absolute(Box::new(self.value.math_layout())),
+            SingleOperator::Cube => power_layout(&self.value, "3"),
+            SingleOperator::CubeRoot => MathBox::Root(
+                Box::new(MathBox::text("3")),
+                Box::new(self.value.math_layout()),
+            ),
+            SingleOperator::Power(exponent) => power_layout(&self.value, &exponent.to_string()),
         }
     }
 }
 
-#[derive(Debug)]
+impl SingleTreeNode {
+    fn checked_value(&self) -> Result<Rational, AnswerParseError> {
+        let sub_value = self.value.checked_value()?;
+        let value = match self.operator {
+            SingleOperator::Brackets => Some(sub_value),
+            SingleOperator::Square => sub_value.checked_pow(2),
+            SingleOperator::SquareRoot => {
+                return sub_value.nth_root(2).ok_or(AnswerParseError::InexactRoot);
+            }
+            SingleOperator::Negate => sub_value.checked_neg(),
+            SingleOperator::Absolute => sub_value.checked_abs(),
+            SingleOperator::Cube => sub_value.checked_pow(3),
+            SingleOperator::CubeRoot => {
+                return sub_value.nth_root(3).ok_or(AnswerParseError::InexactRoot);
+            }
+            SingleOperator::Power(exponent) => sub_value.checked_pow(exponent),
+        };
+        value.ok_or(AnswerParseError::TooLarge)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 enum DoubleOperator {
     Add,
     Subtract,
@@ -332,8 +1062,17 @@ enum DoubleOperator {
     Divide,
 }
 
+impl DoubleOperator {
+    fn precedence(&self) -> Precedence {
+        match self {
+            DoubleOperator::Add | DoubleOperator::Subtract => Precedence::Sum,
+            DoubleOperator::Multiply | DoubleOperator::Divide => Precedence::Product,
+        }
+    }
+}
+
 impl Distribution<DoubleOperator> for StandardUniform {
-    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> DoubleOperator {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> DoubleOperator {
         match rng.random_range(0..4) {
             0 => DoubleOperator::Add,
             1 => DoubleOperator::Subtract,
@@ -344,9 +1083,379 @@ impl Distribution<DoubleOperator> for StandardUniform {
     }
 }
 
-#[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 enum SingleOperator {
+    /// Brackets written into an equation by hand, generated ones only get them where needed
     Brackets,
     Square,
+    SquareRoot,
+    Negate,
+    Absolute,
+    Cube,
+    CubeRoot,
+    /// Any other whole exponent, squares and cubes have their own
+    Power(u32),
+}
+
+impl SingleOperator {
+    fn precedence(&self) -> Precedence {
+        match self {
+            SingleOperator::Brackets | SingleOperator::Absolute => Precedence::Atom,
+            SingleOperator::Square | SingleOperator::Cube | SingleOperator::Power(_) => {
+                Precedence::Postfix
+            }
+            SingleOperator::SquareRoot | SingleOperator::CubeRoot | SingleOperator::Negate => {
+                Precedence::Prefix
+            }
+        }
+    }
+}
+
+/// Writes a whole number with superscript digits, e.g. `⁴`
+fn superscript(number: u32) -> String {
+    number
+        .to_string()
+        .chars()
+        .map(|digit| SUPERSCRIPT_DIGITS[digit.to_digit(10).unwrap_or(0) as usize])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::{
+        DoubleOperator, DoubleTreeNode, LeafNode, Node, SingleOperator, SingleTreeNode, TreeNode,
+        find_divisor, generate_equation_for_value, new_configured_equation,
+        new_order_of_operations_equation, new_power_equation, new_root_equation, parse_equation,
+    };
+    use crate::equation::{
+        Evaluate,
+        answer::{AnswerParseError, parse_answer},
+        config::{GeneratorConfig, OperatorWeights},
+        layout::{MathBox, MathLayout},
+        rational::Rational,
+    };
+
+    fn leaf(mantissa: i64, places: u32) -> Node {
+        Node::LeafNode(LeafNode::new(Rational::from_decimal(mantissa, places)))
+    }
+
+    fn double(operator: DoubleOperator, left: Node, right: Node) -> Node {
+        Node::TreeNode(TreeNode::DoubleTreeNode(DoubleTreeNode {
+            operator,
+            left_value: Box::new(left),
+            right_value: Box::new(right),
+        }))
+    }
+
+    fn single(operator: SingleOperator, value: Node) -> Node {
+        Node::TreeNode(TreeNode::SingleTreeNode(SingleTreeNode {
+            operator,
+            value: Box::new(value),
+        }))
+    }
+
+    fn leaves_are_decimals(node: &Node) -> bool {
+        match node {
+            Node::LeafNode(leaf_node) => leaf_node.value.decimal_places().is_some(),
+            Node::TreeNode(TreeNode::DoubleTreeNode(double_tree_node)) => {
+                leaves_are_decimals(&double_tree_node.left_value)
+                    && leaves_are_decimals(&double_tree_node.right_value)
+            }
+            Node::TreeNode(TreeNode::SingleTreeNode(single_tree_node)) => {
+                leaves_are_decimals(&single_tree_node.value)
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn generated_tree_evaluates_to_target(
+            mantissa in -5000i64..5000,
+            places in 0u32..3,
+            depth in 0i32..4,
+            seed: u64,
+        ) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let target = Rational::from_decimal(mantissa, places);
+            let node =
+                generate_equation_for_value(&mut rng, &GeneratorConfig::default(), depth, target);
+
+            prop_assert_eq!(node.calc_value(), target);
+            prop_assert!(leaves_are_decimals(&node), "{} has a non-decimal leaf", node);
+        }
+
+        #[test]
+        fn divisors_dont_add_decimal_places(
+            mantissa in -50_000i64..50_000,
+            places in 0u32..4,
+            decimal_places in 0u32..=GeneratorConfig::MAX_DECIMAL_PLACES,
+            max_operand in 1i64..=GeneratorConfig::MAX_OPERAND,
+            seed: u64,
+        ) {
+            let config = GeneratorConfig {
+                max_operand,
+                decimal_places,
+                ..GeneratorConfig::MEDIUM
+            };
+            let mut rng = StdRng::seed_from_u64(seed);
+            let end_value = Rational::from_decimal(mantissa, places);
+            let divisor = find_divisor(&mut rng, &config, end_value);
+
+            prop_assert!(divisor > Rational::ZERO);
+            prop_assert!(divisor <= Rational::from(max_operand.max(50)));
+            prop_assert!(divisor.decimal_places() <= Some(decimal_places));
+            let product_places = (divisor * end_value).decimal_places().unwrap();
+            prop_assert!(product_places <= places.max(decimal_places));
+        }
+
+        #[test]
+        fn generates_no_negatives_when_not_allowed(seed: u64, depth in 0i32..4) {
+            let config = GeneratorConfig {
+                min_depth: depth,
+                max_depth: depth,
+                min_operand: 0,
+                allow_negatives: false,
+                ..GeneratorConfig::MEDIUM
+            };
+            let mut rng = StdRng::seed_from_u64(seed);
+            let equation = new_configured_equation(&mut rng, &config);
+
+            prop_assert!(values_are_non_negative(&equation.node), "{} has a negative", equation);
+        }
+
+        #[test]
+        fn printed_equation_parses_to_same_value(seed: u64) {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let equation = new_configured_equation(&mut rng, &GeneratorConfig::COMPLEX);
+
+            prop_assert_eq!(parse_answer(&equation.to_string()), Ok(equation.calc_value()));
+        }
+    }
+
+    #[test]
+    fn follows_generator_config() {
+        let config = GeneratorConfig {
+            min_depth: 0,
+            max_depth: 0,
+            min_operand: -12,
+            max_operand: 12,
+            decimal_places: 0,
+            operator_weights: OperatorWeights {
+                add: 0,
+                subtract: 0,
+                multiply: 1,
+                divide: 0,
+            },
+            single_operator_chance: 0.0,
+            allow_negatives: true,
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            let equation = new_configured_equation(&mut rng, &config);
+            match &equation.node {
+                Node::TreeNode(TreeNode::DoubleTreeNode(double_tree_node)) => {
+                    assert!(matches!(
+                        double_tree_node.operator,
+                        DoubleOperator::Multiply
+                    ));
+                    assert!(leaves_are_integers(&equation.node), "{}", equation);
+                }
+                _ => panic!("{} should be a single multiplication", equation),
+            }
+        }
+    }
+
+    fn values_are_non_negative(node: &Node) -> bool {
+        node.calc_value() >= Rational::ZERO
+            && match node {
+                Node::LeafNode(_) => true,
+                Node::TreeNode(TreeNode::DoubleTreeNode(double_tree_node)) => {
+                    values_are_non_negative(&double_tree_node.left_value)
+                        && values_are_non_negative(&double_tree_node.right_value)
+                }
+                Node::TreeNode(TreeNode::SingleTreeNode(single_tree_node)) => {
+                    values_are_non_negative(&single_tree_node.value)
+                }
+            }
+    }
+
+    fn leaves_are_integers(node: &Node) -> bool {
+        match node {
+            Node::LeafNode(leaf_node) => leaf_node.value.is_integer(),
+            Node::TreeNode(TreeNode::DoubleTreeNode(double_tree_node)) => {
+                leaves_are_integers(&double_tree_node.left_value)
+                    && leaves_are_integers(&double_tree_node.right_value)
+            }
+            Node::TreeNode(TreeNode::SingleTreeNode(single_tree_node)) => {
+                leaves_are_integers(&single_tree_node.value)
+            }
+        }
+    }
+
+    #[test]
+    fn prints_only_needed_brackets() {
+        let node = double(
+            DoubleOperator::Multiply,
+            double(DoubleOperator::Add, leaf(12, 1), leaf(34, 1)),
+            double(DoubleOperator::Subtract, leaf(50, 1), leaf(21, 1)),
+        );
+        assert_eq!(node.to_string(), "(1.2 + 3.4) × (5 - 2.1)");
+
+        let node = double(
+            DoubleOperator::Add,
+            double(DoubleOperator::Multiply, leaf(2, 0), leaf(3, 0)),
+            double(DoubleOperator::Subtract, leaf(4, 0), leaf(-5, 0)),
+        );
+        assert_eq!(node.to_string(), "2 × 3 + 4 - (-5)");
+
+        let node = double(
+            DoubleOperator::Divide,
+            leaf(8, 0),
+            double(DoubleOperator::Divide, leaf(4, 0), leaf(2, 0)),
+        );
+        assert_eq!(node.to_string(), "8 ÷ (4 ÷ 2)");
+
+        let node = single(
+            SingleOperator::Negate,
+            single(SingleOperator::Square, leaf(-3, 0)),
+        );
+        assert_eq!(node.to_string(), "-(-3)²");
+
+        let node = double(
+            DoubleOperator::Subtract,
+            single(SingleOperator::SquareRoot, leaf(16, 0)),
+            single(
+                SingleOperator::Absolute,
+                double(DoubleOperator::Add, leaf(-1, 0), leaf(2, 0)),
+            ),
+        );
+        assert_eq!(node.to_string(), "√16 - |-1 + 2|");
+    }
+
+    #[test]
+    fn same_seed_generates_same_equations() {
+        let mut first_rng = StdRng::seed_from_u64(42);
+        let mut second_rng = StdRng::seed_from_u64(42);
+        for _ in 0..10 {
+            assert_eq!(
+                new_configured_equation(&mut first_rng, &GeneratorConfig::COMPLEX).to_string(),
+                new_configured_equation(&mut second_rng, &GeneratorConfig::COMPLEX).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn lays_out_division_as_fraction() {
+        let node = single(
+            SingleOperator::Square,
+            double(
+                DoubleOperator::Divide,
+                double(DoubleOperator::Add, leaf(1, 0), leaf(2, 0)),
+                leaf(-4, 0),
+            ),
+        );
+        let fraction = MathBox::Fraction(
+            Box::new(MathBox::Row(vec![
+                MathBox::text("1"),
+                MathBox::text("+"),
+                MathBox::text("2"),
+            ])),
+            Box::new(MathBox::text("-4")),
+        );
+        assert_eq!(
+            node.math_layout(),
+            MathBox::Power(
+                Box::new(MathBox::Brackets(Box::new(fraction))),
+                Box::new(MathBox::text("2"))
+            )
+        );
+    }
+
+    #[test]
+    fn writes_powers_and_roots() {
+        let power = single(SingleOperator::Power(4), leaf(3, 0));
+        assert_eq!(power.to_string(), "3⁴");
+        assert_eq!(power.calc_value(), Rational::from(81));
+        assert_eq!(power.math_layout().to_latex(), "{3}^{4}");
+
+        let root = single(SingleOperator::CubeRoot, leaf(27, 0));
+        assert_eq!(root.to_string(), "∛27");
+        assert_eq!(root.calc_value(), Rational::from(3));
+        assert_eq!(root.math_layout().to_latex(), "\\sqrt[3]{27}");
+    }
+
+    #[test]
+    fn order_of_operations_needs_no_brackets() {
+        let mut rng = StdRng::seed_from_u64(20);
+        for _ in 0..100 {
+            let equation = new_order_of_operations_equation(&mut rng);
+            let text = equation.to_string();
+            assert!(!text.contains('('), "{}", text);
+            assert!(equation.calc_value().is_integer(), "{}", text);
+            assert_eq!(parse_answer(&text), Ok(equation.calc_value()));
+
+            assert!(new_power_equation(&mut rng).calc_value() <= Rational::from(1024));
+            assert!(new_root_equation(&mut rng).calc_value().is_integer());
+        }
+    }
+
+    #[test]
+    fn parses_written_equations() {
+        for text in [
+            "(3 + 4) × 2²",
+            "√16 - |-5| ÷ 2",
+            "∛27 + 2⁵",
+            "-3² + (-2)³",
+            "1.5 - (2 - 3)",
+        ] {
+            let equation = parse_equation(text).unwrap();
+            assert_eq!(equation.to_string(), text);
+            assert_eq!(Ok(equation.calc_value()), parse_answer(text));
+        }
+        assert_eq!(
+            parse_equation("(3 + 4) × 2²")
+                .unwrap()
+                .math_layout()
+                .to_latex(),
+            "\\left( 3 + 4 \\right) \\times {2}^{2}"
+        );
+
+        assert_eq!(
+            parse_equation("3 ÷ (2 - 2)"),
+            Err(AnswerParseError::DivideByZero)
+        );
+        assert_eq!(parse_equation("√8"), Err(AnswerParseError::InexactRoot));
+        for text in [
+            "(".repeat(200_000) + "1",
+            "-".repeat(200_000) + "1",
+            "+".repeat(200_000) + "1",
+            "|".repeat(200_000),
+            "1".to_string() + &" - 1".repeat(200_000),
+            "2".to_string() + &" ²".repeat(200_000),
+        ] {
+            assert_eq!(parse_equation(&text), Err(AnswerParseError::TooDeep));
+            assert_eq!(parse_answer(&text), Err(AnswerParseError::TooDeep));
+        }
+        let nested = format!("{}1{}", "(".repeat(50), ")".repeat(50));
+        assert_eq!(parse_answer(&nested), Ok(Rational::ONE));
+        assert_eq!(
+            parse_equation("1 1/2"),
+            Err(AnswerParseError::UnexpectedToken("1".to_string()))
+        );
+    }
+
+    #[test]
+    fn generated_equations_parse_back() {
+        let mut rng = StdRng::seed_from_u64(23);
+        for _ in 0..100 {
+            let equation = new_configured_equation(&mut rng, &GeneratorConfig::COMPLEX);
+            let parsed = parse_equation(&equation.to_string()).unwrap();
+            assert_eq!(parsed.to_string(), equation.to_string());
+            assert_eq!(parsed.calc_value(), equation.calc_value());
+        }
+    }
 }
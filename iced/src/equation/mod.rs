@@ -1,12 +1,113 @@
 use std::fmt::{Debug, Display};
 
+use answer::{Answer, AnswerParseError};
+use layout::MathLayout;
+use rational::Rational;
+use saved::SavedQuestion;
+
+pub mod algebra;
+pub mod answer;
+pub mod config;
+pub mod fraction;
+pub mod layout;
 pub mod medium;
+pub mod pack;
+pub mod practice;
+pub mod rational;
+pub mod remainder;
+pub mod saved;
 pub mod simple;
 
-pub trait Equation: Display + Debug {
-    fn calc_value(&self) -> f64;
+/// A question with a correct answer of some type, e.g. a number or a quotient and remainder
+pub trait Equation: Display + Debug + MathLayout {
+    type Answer: Answer;
+
+    fn answer(&self) -> Self::Answer;
+
+    /// Whether the player's answer is right, by default when it equals the correct answer
+    fn check(&self, answer: &Self::Answer) -> bool {
+        *answer == self.answer()
+    }
+
+    /// The question as something which can be saved, `None` for parts of questions
+    fn saved(&self) -> Option<SavedQuestion> {
+        None
+    }
+}
+
+/// Something which works out to a single number
+pub trait Evaluate {
+    fn calc_value(&self) -> Rational;
+
+    fn compare_value(&self, value: Rational) -> bool {
+        self.calc_value() == value
+    }
+
+    /// The question as something which can be saved, `None` for parts of questions
+    fn saved(&self) -> Option<SavedQuestion> {
+        None
+    }
+}
+
+impl<T: Evaluate + Display + Debug + MathLayout> Equation for T {
+    type Answer = Rational;
+
+    fn answer(&self) -> Rational {
+        self.calc_value()
+    }
+
+    fn check(&self, answer: &Rational) -> bool {
+        self.compare_value(*answer)
+    }
+
+    fn saved(&self) -> Option<SavedQuestion> {
+        Evaluate::saved(self)
+    }
+}
+
+/// An equation with its answer type hidden, so questions with different kinds of answer can be
+/// asked one after another
+pub trait Question: Display + Debug + MathLayout {
+    /// Checks an answer typed in by the player
+    fn check_answer(&self, text: &str) -> Result<bool, AnswerParseError>;
+
+    /// The correct answer written the way the player would type it
+    fn answer_text(&self) -> String;
+
+    /// Shown in the empty answer box
+    fn answer_hint(&self) -> &'static str;
+
+    /// The question as something which can be saved and asked again
+    fn to_saved(&self) -> Option<SavedQuestion>;
+
+    /// The equation as presentation MathML, the same markup the svelte app explains
+    fn to_mathml(&self) -> String {
+        format!(
+            "<math xmlns=\"http://www.w3.org/1998/Math/MathML\" display=\"block\">{}</math>",
+            self.math_layout().to_mathml()
+        )
+    }
+
+    /// The equation as LaTeX, e.g. for pasting into worksheets
+    fn to_latex(&self) -> String {
+        self.math_layout().to_latex()
+    }
+}
+
+impl<T: Equation> Question for T {
+    fn check_answer(&self, text: &str) -> Result<bool, AnswerParseError> {
+        T::Answer::parse(text).map(|answer| self.check(&answer))
+    }
+
+    fn answer_text(&self) -> String {
+        self.answer().to_string()
+    }
+
+    fn answer_hint(&self) -> &'static str {
+        T::Answer::hint()
+    }
 
-    fn compare_value(&self, value: f64) -> bool {
-        f64::abs(self.calc_value() - value) < 0.001
+    fn to_saved(&self) -> Option<SavedQuestion> {
+        self.saved()
     }
 }
@@ -0,0 +1,202 @@
+// question packs, sets of equations written out by hand to be asked in order
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Question,
+    answer::AnswerParseError,
+    medium::parse_equation,
+    saved::{LoadError, SCHEMA_VERSION, SavedQuestion, check_version},
+};
+
+/// A pack as it's written in a file, with each question written like `(3 + 4) × 2²`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuestionPack {
+    pub version: u32,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub questions: Vec<String>,
+}
+
+/// A pack whose questions have all been read and checked
+#[derive(Debug, Clone)]
+pub struct ImportedPack {
+    pub pack: QuestionPack,
+    pub questions: Vec<SavedQuestion>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackError {
+    Load(LoadError),
+    NoQuestions,
+    /// A question which couldn't be read, numbered from one
+    Question {
+        number: usize,
+        text: String,
+        err: AnswerParseError,
+    },
+}
+
+impl QuestionPack {
+    pub fn new(title: impl Into<String>, questions: Vec<String>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            title: title.into(),
+            author: None,
+            description: None,
+            questions,
+        }
+    }
+
+    /// A pack of the questions which read back from their text as the same question, along
+    /// with how many were skipped. Only expressions do, others like `17 ÷ 5` with a remainder
+    /// or `2x + 1 = 7` would be asked as something else or can't be read at all
+    pub fn from_questions<'a>(
+        title: impl Into<String>,
+        questions: impl IntoIterator<Item = &'a dyn Question>,
+    ) -> (Self, usize) {
+        let mut skipped = 0;
+        let questions = questions
+            .into_iter()
+            .filter_map(|question| {
+                let text = question.to_string();
+                let is_expression = matches!(
+                    question.to_saved(),
+                    Some(SavedQuestion::Expression(_) | SavedQuestion::Simple(_))
+                );
+                let reads_back = is_expression
+                    && parse_equation(&text)
+                        .is_ok_and(|equation| equation.answer_text() == question.answer_text());
+                if !reads_back {
+                    skipped += 1;
+                }
+                reads_back.then_some(text)
+            })
+            .collect();
+        (Self::new(title, questions), skipped)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("packs should always serialise")
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, PackError> {
+        check_version(text).map_err(PackError::Load)?;
+        serde_json::from_str(text).map_err(|err| PackError::Load(LoadError::Json(err.to_string())))
+    }
+
+    /// Reads every question, failing on the first one which can't be read or doesn't work out
+    pub fn import(self) -> Result<ImportedPack, PackError> {
+        if self.questions.is_empty() {
+            return Err(PackError::NoQuestions);
+        }
+        let questions = self
+            .questions
+            .iter()
+            .enumerate()
+            .map(|(index, text)| {
+                parse_equation(text)
+                    .map(SavedQuestion::Expression)
+                    .map_err(|err| PackError::Question {
+                        number: index + 1,
+                        text: text.clone(),
+                        err,
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ImportedPack {
+            pack: self,
+            questions,
+        })
+    }
+}
+
+impl Display for PackError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PackError::Load(err) => write!(f, "{}", err),
+            PackError::NoQuestions => write!(f, "The pack doesn't have any questions"),
+            PackError::Question { number, text, err } => {
+                write!(f, "Question {} \"{}\": {}", number, text, err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::equation::{
+        algebra::new_linear_equation, config::GeneratorConfig, fraction::new_fraction_equation,
+        medium::new_configured_equation, remainder::new_remainder_equation,
+        simple::new_times_table_equation,
+    };
+
+    #[test]
+    fn imports_packs_in_order() {
+        let text = r#"{
+            "version": 1,
+            "title": "Order of operations",
+            "author": "Room 4",
+            "questions": ["3 + 4 × 2", "(3 + 4) × 2", "√16 + 2³"]
+        }"#;
+        let imported = QuestionPack::from_json(text).unwrap().import().unwrap();
+        assert_eq!(imported.pack.author.as_deref(), Some("Room 4"));
+        assert_eq!(imported.pack.description, None);
+        let answers: Vec<_> = imported
+            .questions
+            .into_iter()
+            .map(|question| question.into_question().answer_text())
+            .collect();
+        assert_eq!(answers, ["11", "14", "12"]);
+    }
+
+    #[test]
+    fn reports_the_question_which_is_wrong() {
+        let pack = QuestionPack::new("Broken", vec!["1 + 1".to_string(), "4 ÷ 0".to_string()]);
+        let err = QuestionPack::from_json(&pack.to_json())
+            .unwrap()
+            .import()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Question 2 \"4 ÷ 0\": Can't divide by zero"
+        );
+        assert_eq!(
+            QuestionPack::new("Empty", Vec::new()).import().unwrap_err(),
+            PackError::NoQuestions
+        );
+    }
+
+    #[test]
+    fn exports_expressions() {
+        let mut rng = StdRng::seed_from_u64(23);
+        let expression = new_configured_equation(&mut rng, &GeneratorConfig::COMPLEX);
+        let times_table = new_times_table_equation(&mut rng, 2..=12);
+        // these would read back as a division and a sum with decimal answers
+        let remainder = new_remainder_equation(&mut rng);
+        let fraction = new_fraction_equation(&mut rng, false);
+        let linear = new_linear_equation(&mut rng);
+        let questions: [&dyn Question; 5] =
+            [&expression, &remainder, &times_table, &fraction, &linear];
+        let (pack, skipped) = QuestionPack::from_questions("Session", questions);
+        assert_eq!(skipped, 3);
+        let imported = pack.import().unwrap();
+        let answers: Vec<_> = imported
+            .questions
+            .into_iter()
+            .map(|question| question.into_question().answer_text())
+            .collect();
+        assert_eq!(
+            answers,
+            [expression.answer_text(), times_table.answer_text()]
+        );
+    }
+}
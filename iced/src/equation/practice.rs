@@ -0,0 +1,161 @@
+// practice sets which drill one skill at a time
+
+use std::fmt::Display;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Question,
+    algebra::{new_linear_equation, new_quadratic_equation},
+    config::{GeneratorConfig, OperatorWeights},
+    fraction::{new_fraction_equation, new_simplify_equation},
+    medium::{
+        new_configured_equation, new_order_of_operations_equation, new_power_equation,
+        new_root_equation,
+    },
+    remainder::{new_modulo_equation, new_remainder_equation},
+    simple::{new_negative_addition_equation, new_times_table_equation},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PracticeSet {
+    /// Multiplying by 2 to 12
+    TimesTables,
+    /// The times tables backwards, dividing exactly by 2 to 12
+    DivisionDrills,
+    /// Adding where at least one number is negative
+    NegativeAddition,
+    /// Dividing by 2 to 12 and giving what's left over
+    DivisionWithRemainders,
+    Modulo,
+    /// Solving for x in equations like `3x + 2 = 14`
+    LinearEquations,
+    /// Finding both roots of quadratics which factorise
+    QuadraticEquations,
+    /// Adding, subtracting and multiplying fractions
+    Fractions,
+    SimplifyingFractions,
+    /// Small whole numbers raised to powers up to 5
+    Powers,
+    /// Square roots of perfect squares and cube roots of perfect cubes
+    Roots,
+    /// Sums of products and powers without brackets, e.g. `3 + 4 × 2²`
+    OrderOfOperations,
+}
+
+/// Settings which only some practice sets use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PracticeOptions {
+    /// Whether fraction answers can be given as equivalent decimals instead of lowest terms
+    pub fraction_decimals: bool,
+}
+
+/// Whole number divisions with a divisor and answer from 1 to 12
+const DIVISION_DRILLS: GeneratorConfig = GeneratorConfig {
+    min_depth: 0,
+    max_depth: 0,
+    min_operand: 1,
+    max_operand: 12,
+    decimal_places: 0,
+    operator_weights: OperatorWeights {
+        add: 0,
+        subtract: 0,
+        multiply: 0,
+        divide: 1,
+    },
+    single_operator_chance: 0.0,
+    allow_negatives: false,
+};
+
+impl PracticeSet {
+    pub const ALL: [PracticeSet; 12] = [
+        PracticeSet::TimesTables,
+        PracticeSet::DivisionDrills,
+        PracticeSet::NegativeAddition,
+        PracticeSet::DivisionWithRemainders,
+        PracticeSet::Modulo,
+        PracticeSet::LinearEquations,
+        PracticeSet::QuadraticEquations,
+        PracticeSet::Fractions,
+        PracticeSet::SimplifyingFractions,
+        PracticeSet::Powers,
+        PracticeSet::Roots,
+        PracticeSet::OrderOfOperations,
+    ];
+
+    pub fn new_equation<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        options: &PracticeOptions,
+    ) -> Box<dyn Question> {
+        match self {
+            PracticeSet::TimesTables => Box::new(new_times_table_equation(rng, 2..=12)),
+            PracticeSet::DivisionDrills => Box::new(new_configured_equation(rng, &DIVISION_DRILLS)),
+            PracticeSet::NegativeAddition => Box::new(new_negative_addition_equation(rng)),
+            PracticeSet::DivisionWithRemainders => Box::new(new_remainder_equation(rng)),
+            PracticeSet::Modulo => Box::new(new_modulo_equation(rng)),
+            PracticeSet::LinearEquations => Box::new(new_linear_equation(rng)),
+            PracticeSet::QuadraticEquations => Box::new(new_quadratic_equation(rng)),
+            PracticeSet::Fractions => {
+                Box::new(new_fraction_equation(rng, options.fraction_decimals))
+            }
+            PracticeSet::SimplifyingFractions => {
+                Box::new(new_simplify_equation(rng, options.fraction_decimals))
+            }
+            PracticeSet::Powers => Box::new(new_power_equation(rng)),
+            PracticeSet::Roots => Box::new(new_root_equation(rng)),
+            PracticeSet::OrderOfOperations => Box::new(new_order_of_operations_equation(rng)),
+        }
+    }
+}
+
+impl PracticeSet {
+    /// Whether the answers are fractions, so [`PracticeOptions::fraction_decimals`] applies
+    pub fn has_fraction_answers(&self) -> bool {
+        matches!(
+            self,
+            PracticeSet::Fractions | PracticeSet::SimplifyingFractions
+        )
+    }
+}
+
+impl Display for PracticeSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            PracticeSet::TimesTables => "Times tables 2-12",
+            PracticeSet::DivisionDrills => "Division drills",
+            PracticeSet::NegativeAddition => "Negative number addition",
+            PracticeSet::DivisionWithRemainders => "Division with remainders",
+            PracticeSet::Modulo => "Modulo",
+            PracticeSet::LinearEquations => "Solve for x",
+            PracticeSet::QuadraticEquations => "Solve quadratics for x",
+            PracticeSet::Fractions => "Fractions",
+            PracticeSet::SimplifyingFractions => "Simplifying fractions",
+            PracticeSet::Powers => "Powers",
+            PracticeSet::Roots => "Square and cube roots",
+            PracticeSet::OrderOfOperations => "Order of operations",
+        };
+        write!(f, "{}", string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::DIVISION_DRILLS;
+    use crate::equation::{Evaluate, medium::new_configured_equation, rational::Rational};
+
+    #[test]
+    fn division_drills_have_whole_answers() {
+        let mut rng = StdRng::seed_from_u64(12);
+        for _ in 0..50 {
+            let equation = new_configured_equation(&mut rng, &DIVISION_DRILLS);
+            let answer = equation.calc_value();
+            assert!(answer.is_integer(), "{} = {}", equation, answer);
+            assert!(answer >= Rational::ONE && answer <= Rational::from(12));
+            assert!(equation.to_string().contains('÷'), "{}", equation);
+        }
+    }
+}
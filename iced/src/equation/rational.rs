@@ -0,0 +1,388 @@
+// exact rational numbers so generated answers don't pick up floating point error
+
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// The most decimal places written out, longer decimals are written as a fraction instead. Any
+/// more and the power of ten doesn't fit
+const MAX_DECIMAL_PLACES: u32 = 18;
+
+/// A fraction kept in lowest terms with a positive denominator. Saved as the text [`Display`]
+/// writes so it stays exact
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Rational {
+    numerator: i64,
+    denominator: i64,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational {
+        numerator: 0,
+        denominator: 1,
+    };
+
+    pub const ONE: Rational = Rational {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// Creates a new rational, panics if the denominator is zero or it doesn't fit
+    pub fn new(numerator: i64, denominator: i64) -> Self {
+        Self::checked_new(i128::from(numerator), i128::from(denominator))
+            .expect("rational should have a non-zero denominator and fit in an i64")
+    }
+
+    /// Creates a rational from a decimal, e.g. `(125, 2)` is 1.25
+    pub fn from_decimal(mantissa: i64, decimal_places: u32) -> Self {
+        Self::new(mantissa, 10i64.pow(decimal_places))
+    }
+
+    fn checked_new(numerator: i128, denominator: i128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let divisor = gcd(numerator, denominator);
+        let sign = denominator.signum();
+        Some(Self {
+            numerator: i64::try_from(sign * numerator / divisor).ok()?,
+            denominator: i64::try_from(sign * denominator / divisor).ok()?,
+        })
+    }
+
+    pub fn numerator(&self) -> i64 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i64 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator == 1
+    }
+
+    pub fn abs(&self) -> Self {
+        self.checked_abs()
+            .expect("rational absolute value overflowed")
+    }
+
+    /// The size without the sign, `None` for the one negative value with no positive to match
+    pub fn checked_abs(&self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_abs()?,
+            denominator: self.denominator,
+        })
+    }
+
+    pub fn checked_neg(self) -> Option<Self> {
+        Some(Self {
+            numerator: self.numerator.checked_neg()?,
+            denominator: self.denominator,
+        })
+    }
+
+    /// The largest integer which isn't bigger than this
+    pub fn floor(&self) -> i64 {
+        self.numerator.div_euclid(self.denominator)
+    }
+
+    /// The number of decimal places needed to write this exactly, or `None` if the decimal
+    /// doesn't terminate (e.g. 1/3)
+    pub fn decimal_places(&self) -> Option<u32> {
+        let mut denominator = self.denominator;
+        let mut twos = 0;
+        let mut fives = 0;
+        while denominator % 2 == 0 {
+            denominator /= 2;
+            twos += 1;
+        }
+        while denominator % 5 == 0 {
+            denominator /= 5;
+            fives += 1;
+        }
+        (denominator == 1).then_some(u32::max(twos, fives))
+    }
+
+    /// The exact `n`th root if there is one, e.g. the square root of 2.25 is 1.5
+    pub fn nth_root(&self, n: u32) -> Option<Self> {
+        if self.numerator < 0 && n.is_multiple_of(2) {
+            return None;
+        }
+        let numerator = integer_root(self.numerator.unsigned_abs(), n)?;
+        let denominator = integer_root(self.denominator.unsigned_abs(), n)?;
+        Some(Self::new(
+            self.numerator.signum() * i64::try_from(numerator).ok()?,
+            i64::try_from(denominator).ok()?,
+        ))
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let numerator = i128::from(self.numerator) * i128::from(other.denominator)
+            + i128::from(other.numerator) * i128::from(self.denominator);
+        let denominator = i128::from(self.denominator) * i128::from(other.denominator);
+        Self::checked_new(numerator, denominator)
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let numerator = i128::from(self.numerator) * i128::from(other.denominator)
+            - i128::from(other.numerator) * i128::from(self.denominator);
+        let denominator = i128::from(self.denominator) * i128::from(other.denominator);
+        Self::checked_new(numerator, denominator)
+    }
+
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let numerator = i128::from(self.numerator) * i128::from(other.numerator);
+        let denominator = i128::from(self.denominator) * i128::from(other.denominator);
+        Self::checked_new(numerator, denominator)
+    }
+
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        let numerator = i128::from(self.numerator) * i128::from(other.denominator);
+        let denominator = i128::from(self.denominator) * i128::from(other.numerator);
+        Self::checked_new(numerator, denominator)
+    }
+
+    /// Raises this to a whole power by repeated squaring, so huge exponents of 0, 1 and -1
+    /// don't take forever
+    pub fn checked_pow(self, mut exponent: u32) -> Option<Self> {
+        let mut base = self;
+        let mut power = Self::ONE;
+        while exponent > 0 {
+            if exponent % 2 == 1 {
+                power = power.checked_mul(base)?;
+            }
+            exponent /= 2;
+            if exponent > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Some(power)
+    }
+}
+
+fn integer_root(value: u64, n: u32) -> Option<u64> {
+    let root = (value as f64).powf(1.0 / f64::from(n)).round() as u64;
+    // check either side in case the float root was slightly off
+    (root.saturating_sub(1)..=root + 1).find(|candidate| candidate.checked_pow(n) == Some(value))
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+/// Text which isn't a decimal or fraction that [`Rational`] can hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRationalError(String);
+
+impl Display for ParseRationalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\" isn't a decimal or fraction", self.0)
+    }
+}
+
+impl FromStr for Rational {
+    type Err = ParseRationalError;
+
+    /// Reads what [`Display`] writes, a decimal like `-12.25` or a fraction like `-1/3`
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseRationalError(text.to_string());
+        if let Some((numerator, denominator)) = text.split_once('/') {
+            let numerator = numerator.parse::<i64>().map_err(|_| invalid())?;
+            let denominator = denominator.parse::<i64>().map_err(|_| invalid())?;
+            return Self::checked_new(i128::from(numerator), i128::from(denominator))
+                .ok_or_else(invalid);
+        }
+        let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+        if fraction.len() > MAX_DECIMAL_PLACES as usize
+            || !fraction.chars().all(|digit| digit.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let mantissa = format!("{}{}", whole, fraction)
+            .parse::<i64>()
+            .map_err(|_| invalid())?;
+        Ok(Self::from_decimal(mantissa, fraction.len() as u32))
+    }
+}
+
+impl TryFrom<String> for Rational {
+    type Error = ParseRationalError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Rational> for String {
+    fn from(value: Rational) -> Self {
+        value.to_string()
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(value: i32) -> Self {
+        Self::new(i64::from(value), 1)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Self::new(value, 1)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, other: Self) -> Self::Output {
+        self.checked_add(other)
+            .expect("rational addition overflowed")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, other: Self) -> Self::Output {
+        self.checked_sub(other)
+            .expect("rational subtraction overflowed")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, other: Self) -> Self::Output {
+        self.checked_mul(other)
+            .expect("rational multiplication overflowed")
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, other: Self) -> Self::Output {
+        self.checked_div(other)
+            .expect("rational division by zero or overflow")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("rational negation overflowed")
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let left = i128::from(self.numerator) * i128::from(other.denominator);
+        let right = i128::from(other.numerator) * i128::from(self.denominator);
+        left.cmp(&right)
+    }
+}
+
+impl Display for Rational {
+    /// Writes terminating decimals exactly (e.g. `-12.25`) and anything else, or any decimal too
+    /// long to read back, as a fraction
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.decimal_places() {
+            Some(0) => write!(f, "{}", self.numerator),
+            Some(places) if places <= MAX_DECIMAL_PLACES => {
+                let scale = 10i128.pow(places);
+                let scaled = i128::from(self.numerator) * scale / i128::from(self.denominator);
+                let sign = if scaled < 0 { "-" } else { "" };
+                let whole = scaled.abs() / scale;
+                let fraction = scaled.abs() % scale;
+                write!(
+                    f,
+                    "{}{}.{:0width$}",
+                    sign,
+                    whole,
+                    fraction,
+                    width = places as usize
+                )
+            }
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_long_decimals_as_fractions() {
+        assert_eq!(Rational::new(-49, 4).to_string(), "-12.25");
+        assert_eq!(
+            Rational::new(1, 1 << 18).to_string(),
+            "0.000003814697265625"
+        );
+        let long = Rational::new(1, 1 << 40);
+        assert_eq!(long.to_string(), "1/1099511627776");
+        assert_eq!(long.to_string().parse(), Ok(long));
+    }
+
+    #[test]
+    fn raises_to_huge_powers() {
+        assert_eq!(
+            Rational::new(3, 2).checked_pow(5),
+            Some(Rational::new(243, 32))
+        );
+        assert_eq!(Rational::from(7).checked_pow(0), Some(Rational::ONE));
+        assert_eq!(
+            Rational::ONE.checked_pow(4_000_000_000),
+            Some(Rational::ONE)
+        );
+        assert_eq!(
+            Rational::from(-1).checked_pow(u32::MAX),
+            Some(Rational::from(-1))
+        );
+        assert_eq!(Rational::from(2).checked_pow(63), None);
+        assert_eq!(
+            Rational::from(2).checked_pow(62),
+            Some(Rational::from(1i64 << 62))
+        );
+    }
+
+    #[test]
+    fn negating_the_smallest_value_fails() {
+        let smallest = Rational::from(i64::MIN);
+        assert_eq!(smallest.checked_neg(), None);
+        assert_eq!(smallest.checked_abs(), None);
+        assert_eq!(Rational::ZERO.checked_sub(smallest), None);
+        assert_eq!(
+            Rational::from(-1).checked_sub(smallest),
+            Some(Rational::from(i64::MAX))
+        );
+    }
+}
@@ -0,0 +1,212 @@
+// whole number division questions answered with a remainder, and modulo questions
+
+use std::fmt::Display;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Equation, Evaluate,
+    answer::{Answer, AnswerParseError, parse_quotient_remainder},
+    layout::{MathBox, MathLayout},
+    rational::Rational,
+    saved::SavedQuestion,
+};
+
+/// `a ÷ b`, answered with a quotient and remainder like `3 r 2`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemainderEquation {
+    dividend: i64,
+    divisor: i64,
+}
+
+/// `a mod b`, answered with just the remainder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuloEquation {
+    dividend: i64,
+    divisor: i64,
+}
+
+/// The answer to a division that doesn't go exactly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotientRemainder {
+    pub quotient: Rational,
+    pub remainder: Rational,
+}
+
+impl RemainderEquation {
+    pub fn new(dividend: i64, divisor: i64) -> Self {
+        Self { dividend, divisor }
+    }
+}
+
+impl ModuloEquation {
+    pub fn new(dividend: i64, divisor: i64) -> Self {
+        Self { dividend, divisor }
+    }
+}
+
+/// A dividend and divisor from 2 to 12 where the quotient is up to 12, which often leaves a
+/// remainder
+fn random_division<R: Rng + ?Sized>(rng: &mut R) -> (i64, i64) {
+    let divisor = rng.random_range(2..=12);
+    let quotient = rng.random_range(0..=12);
+    let remainder = rng.random_range(0..divisor);
+    (divisor * quotient + remainder, divisor)
+}
+
+pub fn new_remainder_equation<R: Rng + ?Sized>(rng: &mut R) -> RemainderEquation {
+    let (dividend, divisor) = random_division(rng);
+    RemainderEquation::new(dividend, divisor)
+}
+
+pub fn new_modulo_equation<R: Rng + ?Sized>(rng: &mut R) -> ModuloEquation {
+    let (dividend, divisor) = random_division(rng);
+    ModuloEquation::new(dividend, divisor)
+}
+
+/// The quotient and remainder of a euclidean division, `None` when dividing by zero or it
+/// overflows
+fn checked_division(dividend: i64, divisor: i64) -> Option<(i64, i64)> {
+    Some((
+        dividend.checked_div_euclid(divisor)?,
+        dividend.checked_rem_euclid(divisor)?,
+    ))
+}
+
+impl RemainderEquation {
+    /// Whether the division can be worked out, since a loaded one might divide by zero
+    pub(super) fn works_out(&self) -> bool {
+        checked_division(self.dividend, self.divisor).is_some()
+    }
+}
+
+impl ModuloEquation {
+    pub(super) fn works_out(&self) -> bool {
+        checked_division(self.dividend, self.divisor).is_some()
+    }
+}
+
+impl Equation for RemainderEquation {
+    type Answer = QuotientRemainder;
+
+    fn answer(&self) -> QuotientRemainder {
+        let (quotient, remainder) = checked_division(self.dividend, self.divisor)
+            .expect("divisions should be checked when they're loaded");
+        QuotientRemainder {
+            quotient: Rational::from(quotient),
+            remainder: Rational::from(remainder),
+        }
+    }
+
+    fn saved(&self) -> Option<SavedQuestion> {
+        Some(SavedQuestion::Remainder(self.clone()))
+    }
+}
+
+impl Evaluate for ModuloEquation {
+    fn calc_value(&self) -> Rational {
+        let (_, remainder) = checked_division(self.dividend, self.divisor)
+            .expect("divisions should be checked when they're loaded");
+        Rational::from(remainder)
+    }
+
+    fn saved(&self) -> Option<SavedQuestion> {
+        Some(SavedQuestion::Modulo(self.clone()))
+    }
+}
+
+impl Answer for QuotientRemainder {
+    fn parse(text: &str) -> Result<Self, AnswerParseError> {
+        let (quotient, remainder) = parse_quotient_remainder(text)?;
+        Ok(Self {
+            quotient,
+            remainder,
+        })
+    }
+
+    fn hint() -> &'static str {
+        "Quotient and remainder, e.g. 3 r 2"
+    }
+}
+
+impl MathLayout for RemainderEquation {
+    fn math_layout(&self) -> MathBox {
+        MathBox::Row(vec![
+            MathBox::text(self.dividend.to_string()),
+            MathBox::text("÷"),
+            MathBox::text(self.divisor.to_string()),
+        ])
+    }
+}
+
+impl MathLayout for ModuloEquation {
+    fn math_layout(&self) -> MathBox {
+        MathBox::Row(vec![
+            MathBox::text(self.dividend.to_string()),
+            MathBox::text("mod"),
+            MathBox::text(self.divisor.to_string()),
+        ])
+    }
+}
+
+impl Display for RemainderEquation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ÷ {}", self.dividend, self.divisor)
+    }
+}
+
+impl Display for ModuloEquation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} mod {}", self.dividend, self.divisor)
+    }
+}
+
+impl Display for QuotientRemainder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} r {}", self.quotient, self.remainder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::equation::Question;
+
+    #[test]
+    fn checks_quotient_and_remainder() {
+        let equation = RemainderEquation::new(17, 5);
+        assert_eq!(equation.to_string(), "17 ÷ 5");
+        assert_eq!(equation.answer_text(), "3 r 2");
+        assert_eq!(equation.check_answer("3 r 2"), Ok(true));
+        assert_eq!(equation.check_answer("3r2"), Ok(true));
+        assert_eq!(equation.check_answer("2 r 7"), Ok(false));
+        assert_eq!(
+            equation.check_answer("3.4"),
+            Err(AnswerParseError::MissingRemainder)
+        );
+    }
+
+    #[test]
+    fn checks_modulo() {
+        let equation = ModuloEquation::new(17, 5);
+        assert_eq!(equation.to_string(), "17 mod 5");
+        assert_eq!(equation.to_latex(), "17 \\bmod 5");
+        assert_eq!(equation.check_answer("2"), Ok(true));
+        assert_eq!(equation.check_answer("3"), Ok(false));
+    }
+
+    #[test]
+    fn generated_answers_are_consistent() {
+        let mut rng = StdRng::seed_from_u64(16);
+        for _ in 0..100 {
+            let equation = new_remainder_equation(&mut rng);
+            let answer = equation.answer();
+            assert!(Rational::ZERO <= answer.remainder);
+            assert!(answer.remainder < Rational::from(equation.divisor));
+            assert_eq!(equation.check_answer(&equation.answer_text()), Ok(true));
+        }
+    }
+}
@@ -0,0 +1,257 @@
+// questions saved with their whole structure, so they can be asked again later
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Question,
+    algebra::{LinearEquation, QuadraticEquation},
+    fraction::FractionEquation,
+    medium,
+    remainder::{ModuloEquation, RemainderEquation},
+    simple::SimpleEquation,
+};
+
+/// Bumped whenever the saved format changes, so files from other versions aren't misread
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Any generated question, kept as its tree rather than its text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SavedQuestion {
+    Simple(SimpleEquation),
+    Expression(medium::EquationValue),
+    Remainder(RemainderEquation),
+    Modulo(ModuloEquation),
+    Linear(LinearEquation),
+    Quadratic(QuadraticEquation),
+    Fraction(FractionEquation),
+}
+
+impl SavedQuestion {
+    /// Checks the question can be worked out without panicking, since a hand edited or
+    /// corrupted file could divide by zero, take a root that isn't exact or overflow
+    pub fn check(&self) -> Result<(), LoadError> {
+        let works_out = match self {
+            // the operands are i32s, worked out as i64s so they can't overflow
+            SavedQuestion::Simple(_) => true,
+            SavedQuestion::Expression(equation) => equation.checked_value().is_ok(),
+            SavedQuestion::Remainder(equation) => equation.works_out(),
+            SavedQuestion::Modulo(equation) => equation.works_out(),
+            SavedQuestion::Linear(equation) => equation.works_out(),
+            SavedQuestion::Quadratic(equation) => equation.works_out(),
+            SavedQuestion::Fraction(equation) => equation.works_out(),
+        };
+        if works_out {
+            Ok(())
+        } else {
+            Err(LoadError::InvalidQuestion)
+        }
+    }
+
+    pub fn into_question(self) -> Box<dyn Question> {
+        match self {
+            SavedQuestion::Simple(equation) => Box::new(equation),
+            SavedQuestion::Expression(equation) => Box::new(equation),
+            SavedQuestion::Remainder(equation) => Box::new(equation),
+            SavedQuestion::Modulo(equation) => Box::new(equation),
+            SavedQuestion::Linear(equation) => Box::new(equation),
+            SavedQuestion::Quadratic(equation) => Box::new(equation),
+            SavedQuestion::Fraction(equation) => Box::new(equation),
+        }
+    }
+}
+
+/// Just the version, read first so a file from another version gets a clear error
+#[derive(Deserialize)]
+struct VersionHeader {
+    version: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    Json(String),
+    UnsupportedVersion(u32),
+    /// A question which can't be worked out, see [`SavedQuestion::check`]
+    InvalidQuestion,
+}
+
+/// Checks a saved file has a `version` this build can read
+pub fn check_version(text: &str) -> Result<(), LoadError> {
+    let header: VersionHeader =
+        serde_json::from_str(text).map_err(|err| LoadError::Json(err.to_string()))?;
+    if header.version == SCHEMA_VERSION {
+        Ok(())
+    } else {
+        Err(LoadError::UnsupportedVersion(header.version))
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Json(err) => write!(f, "Couldn't read the questions: {}", err),
+            LoadError::UnsupportedVersion(version) => write!(
+                f,
+                "The questions were saved as version {}, only version {} can be read",
+                version, SCHEMA_VERSION
+            ),
+            LoadError::InvalidQuestion => {
+                write!(f, "One of the questions can't be worked out")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::equation::{config::GeneratorConfig, medium::new_configured_equation};
+
+    /// Questions written out with a version, like they'd be in a saved file
+    #[derive(Deserialize)]
+    struct QuestionFile {
+        questions: Vec<SavedQuestion>,
+    }
+
+    #[test]
+    fn saves_and_restores_questions() {
+        let mut rng = StdRng::seed_from_u64(21);
+        for _ in 0..50 {
+            let equation = new_configured_equation(&mut rng, &GeneratorConfig::COMPLEX);
+            let saved = equation.to_saved().unwrap();
+            let text = serde_json::to_string(&saved).unwrap();
+            let restored: SavedQuestion = serde_json::from_str(&text).unwrap();
+            assert_eq!(restored, saved);
+
+            let question = restored.into_question();
+            assert_eq!(question.to_string(), equation.to_string());
+            assert_eq!(question.answer_text(), equation.answer_text());
+        }
+    }
+
+    #[test]
+    fn writes_tagged_trees() {
+        let text = r#"{
+            "version": 1,
+            "questions": [
+                {
+                    "type": "expression",
+                    "node": {
+                        "node": "tree",
+                        "tree": "double",
+                        "operator": "add",
+                        "left_value": { "node": "leaf", "value": "3" },
+                        "right_value": {
+                            "node": "tree",
+                            "tree": "single",
+                            "operator": { "power": 4 },
+                            "value": { "node": "leaf", "value": "1/2" }
+                        }
+                    }
+                },
+                { "type": "modulo", "dividend": 17, "divisor": 5 }
+            ]
+        }"#;
+        check_version(text).unwrap();
+        let set: QuestionFile = serde_json::from_str(text).unwrap();
+        let questions: Vec<_> = set
+            .questions
+            .iter()
+            .map(|question| {
+                question.check().unwrap();
+                question.clone().into_question()
+            })
+            .collect();
+        assert_eq!(questions[0].to_string(), "3 + 0.5⁴");
+        assert_eq!(questions[0].answer_text(), "3.0625");
+        assert_eq!(questions[1].answer_text(), "2");
+    }
+
+    #[test]
+    fn rejects_questions_which_cant_be_worked_out() {
+        let questions = [
+            r#"{"type": "modulo", "dividend": 17, "divisor": 0}"#,
+            r#"{"type": "remainder", "dividend": -9223372036854775808, "divisor": -1}"#,
+            r#"{
+                "type": "expression",
+                "node": {
+                    "node": "tree",
+                    "tree": "single",
+                    "operator": "square_root",
+                    "value": { "node": "leaf", "value": "2" }
+                }
+            }"#,
+            r#"{
+                "type": "expression",
+                "node": {
+                    "node": "tree",
+                    "tree": "double",
+                    "operator": "divide",
+                    "left_value": { "node": "leaf", "value": "1" },
+                    "right_value": {
+                        "node": "tree",
+                        "tree": "double",
+                        "operator": "subtract",
+                        "left_value": { "node": "leaf", "value": "3" },
+                        "right_value": { "node": "leaf", "value": "3" }
+                    }
+                }
+            }"#,
+            r#"{"type": "linear", "form": "divided", "a": "0", "b": "1", "c": "1", "x": "0"}"#,
+            r#"{"type": "linear", "form": "expanded", "a": "2", "b": "1", "c": "1", "x": "5"}"#,
+            r#"{"type": "quadratic", "b": "1", "c": "-6", "roots": ["2", "3"]}"#,
+            r#"{
+                "type": "fraction",
+                "question": {"simplify": {"numerator": 4, "denominator": 0}},
+                "accept_decimals": false
+            }"#,
+            r#"{
+                "type": "fraction",
+                "question": {
+                    "simplify": {"numerator": -9223372036854775808, "denominator": -1}
+                },
+                "accept_decimals": false
+            }"#,
+        ];
+        for text in questions {
+            let question: SavedQuestion = serde_json::from_str(text).unwrap();
+            assert_eq!(
+                question.check(),
+                Err(LoadError::InvalidQuestion),
+                "{}",
+                text
+            );
+        }
+
+        // operands this big used to overflow an i32
+        let simple: SavedQuestion = serde_json::from_str(
+            r#"{
+                "type": "simple",
+                "equation_value": {
+                    "operator": "multiply",
+                    "value_left": 2147483647,
+                    "value_right": 2147483647
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(simple.check(), Ok(()));
+        assert_eq!(simple.into_question().answer_text(), "4611686014132420609");
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        assert_eq!(
+            check_version(r#"{"version": 2, "questions": []}"#),
+            Err(LoadError::UnsupportedVersion(2))
+        );
+        assert!(matches!(
+            check_version(r#"{"questions": []}"#),
+            Err(LoadError::Json(_))
+        ));
+    }
+}
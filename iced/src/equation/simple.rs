@@ -1,12 +1,18 @@
 // simple equation
 
-use std::fmt::Display;
+use std::{fmt::Display, ops::RangeInclusive};
 
-use rand::distr::{Distribution, StandardUniform};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use super::Equation;
+use super::{
+    Evaluate,
+    layout::{MathBox, MathLayout},
+    rational::Rational,
+    saved::SavedQuestion,
+};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct SimpleEquation {
     equation_value: EquationValue,
 }
@@ -19,10 +25,20 @@ impl SimpleEquation {
     }
 }
 
-impl Equation for SimpleEquation {
-    fn calc_value(&self) -> f64 {
+impl Evaluate for SimpleEquation {
+    fn calc_value(&self) -> Rational {
         self.equation_value.calc_value()
     }
+
+    fn saved(&self) -> Option<SavedQuestion> {
+        Some(SavedQuestion::Simple(self.clone()))
+    }
+}
+
+impl MathLayout for SimpleEquation {
+    fn math_layout(&self) -> MathBox {
+        self.equation_value.math_layout()
+    }
 }
 
 impl Display for SimpleEquation {
@@ -31,16 +47,32 @@ impl Display for SimpleEquation {
     }
 }
 
-pub fn new_simple_equation() -> SimpleEquation {
-    let left = rand::random_range(-10..=10);
-    let right = rand::random_range(-10..=10);
-
-    let operator = rand::random::<BasicOperator>();
+/// A times table question, one side is from the tables and the other from 1 to 12
+pub fn new_times_table_equation<R: Rng + ?Sized>(
+    rng: &mut R,
+    tables: RangeInclusive<i32>,
+) -> SimpleEquation {
+    let table = rng.random_range(tables);
+    let multiple = rng.random_range(1..=12);
+    if rng.random() {
+        SimpleEquation::new(BasicOperator::Multiply, table, multiple)
+    } else {
+        SimpleEquation::new(BasicOperator::Multiply, multiple, table)
+    }
+}
 
-    SimpleEquation::new(operator, left, right)
+/// An addition where at least one side is negative
+pub fn new_negative_addition_equation<R: Rng + ?Sized>(rng: &mut R) -> SimpleEquation {
+    let any = rng.random_range(-20..=20);
+    let negative = rng.random_range(-20..=-1);
+    if rng.random() {
+        SimpleEquation::new(BasicOperator::Add, any, negative)
+    } else {
+        SimpleEquation::new(BasicOperator::Add, negative, any)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 struct EquationValue {
     operator: BasicOperator,
     value_left: i32,
@@ -57,9 +89,19 @@ impl EquationValue {
     }
 }
 
-impl Equation for EquationValue {
-    fn calc_value(&self) -> f64 {
-        f64::from(self.operator.calc(self.value_left, self.value_right))
+impl Evaluate for EquationValue {
+    fn calc_value(&self) -> Rational {
+        Rational::from(self.operator.calc(self.value_left, self.value_right))
+    }
+}
+
+impl MathLayout for EquationValue {
+    fn math_layout(&self) -> MathBox {
+        MathBox::Row(vec![
+            MathBox::text(self.value_left.to_string()),
+            MathBox::text(self.operator.to_string()),
+            MathBox::text(self.value_right.to_string()),
+        ])
     }
 }
 
@@ -73,7 +115,8 @@ impl Display for EquationValue {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 enum BasicOperator {
     Multiply,
     Add,
@@ -81,7 +124,9 @@ enum BasicOperator {
 }
 
 impl BasicOperator {
-    fn calc(&self, left: i32, right: i32) -> i32 {
+    /// Worked out as an i64 so even the biggest operands can't overflow
+    fn calc(&self, left: i32, right: i32) -> i64 {
+        let (left, right) = (i64::from(left), i64::from(right));
         match self {
             BasicOperator::Multiply => left * right,
             BasicOperator::Add => left + right,
@@ -100,14 +145,3 @@ impl Display for BasicOperator {
         write!(f, "{}", operator_char)
     }
 }
-
-impl Distribution<BasicOperator> for StandardUniform {
-    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> BasicOperator {
-        match rng.random_range(0..3) {
-            0 => BasicOperator::Multiply,
-            1 => BasicOperator::Add,
-            2 => BasicOperator::Subtract,
-            _ => panic!("Invalid operator"),
-        }
-    }
-}
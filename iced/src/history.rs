@@ -0,0 +1,223 @@
+// results of finished sessions, saved so they survive restarting the app
+
+use std::{fmt::Display, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use wasm_timer::{SystemTime, UNIX_EPOCH};
+
+use crate::{EquationDifficulty, GameMode, storage};
+
+/// What the history is saved as
+const HISTORY_NAME: &str = "history";
+
+/// The result of a finished session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub game_mode: GameMode,
+    pub equation_difficulty: EquationDifficulty,
+    pub equations_success: u32,
+    pub equations_completed: u32,
+    pub time_taken: Duration,
+    /// Seconds since the unix epoch when the session finished
+    pub date: u64,
+    pub ended_early: bool,
+    /// What the session was set to last for, missing from sessions saved before it was kept
+    #[serde(default)]
+    pub length: Option<SessionLength>,
+}
+
+/// How long a session was set to go on for, only sessions of the same length are compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SessionLength {
+    Questions(u32),
+    Time(Duration),
+}
+
+impl Display for SessionLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionLength::Questions(questions) => write!(f, "{} questions", questions),
+            SessionLength::Time(time) => write!(f, "{} seconds", time.as_secs()),
+        }
+    }
+}
+
+impl SessionRecord {
+    /// Whether this session beats `other`, more correct answers wins then the quickest
+    fn beats(&self, other: &SessionRecord) -> bool {
+        (self.equations_success, other.time_taken) > (other.equations_success, self.time_taken)
+    }
+}
+
+/// Every finished session, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct History {
+    pub sessions: Vec<SessionRecord>,
+}
+
+impl History {
+    /// Loads the saved history, starting a new one if there isn't one or it can't be read
+    pub fn load() -> Self {
+        storage::load_json(HISTORY_NAME).unwrap_or_default()
+    }
+
+    /// Adds a finished session and saves the history
+    pub fn add_session(&mut self, session: SessionRecord) {
+        self.sessions.push(session);
+        storage::save_json(HISTORY_NAME, self);
+    }
+
+    /// The sessions played to the end with the difficulty and game mode
+    fn finished_sessions(
+        &self,
+        equation_difficulty: EquationDifficulty,
+        game_mode: GameMode,
+    ) -> impl Iterator<Item = &SessionRecord> {
+        self.sessions.iter().filter(move |session| {
+            session.equation_difficulty == equation_difficulty
+                && session.game_mode == game_mode
+                && !session.ended_early
+        })
+    }
+
+    /// Every length that's been played to the end with the difficulty and game mode, shortest
+    /// first
+    pub fn session_lengths(
+        &self,
+        equation_difficulty: EquationDifficulty,
+        game_mode: GameMode,
+    ) -> Vec<SessionLength> {
+        let mut lengths: Vec<SessionLength> = self
+            .finished_sessions(equation_difficulty, game_mode)
+            .filter_map(|session| session.length)
+            .collect();
+        lengths.sort();
+        lengths.dedup();
+        lengths
+    }
+
+    /// The best session played to the end with the difficulty, game mode and length
+    pub fn personal_best(
+        &self,
+        equation_difficulty: EquationDifficulty,
+        game_mode: GameMode,
+        length: SessionLength,
+    ) -> Option<&SessionRecord> {
+        self.finished_sessions(equation_difficulty, game_mode)
+            .filter(|session| session.length == Some(length))
+            .fold(None, |best, session| match best {
+                Some(best) if !session.beats(best) => Some(best),
+                _ => Some(session),
+            })
+    }
+}
+
+/// The current time as seconds since the unix epoch
+pub fn current_date() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Formats seconds since the unix epoch as a `YYYY-MM-DD` date in UTC
+pub fn format_date(date: u64) -> String {
+    // from Howard Hinnant's days_from_civil algorithm, run backwards
+    let days = (date / (24 * 60 * 60)) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(equations_success: u32, seconds: u64) -> SessionRecord {
+        SessionRecord {
+            game_mode: GameMode::Standard,
+            equation_difficulty: EquationDifficulty::Simple,
+            equations_success,
+            equations_completed: 10,
+            time_taken: Duration::from_secs(seconds),
+            date: 0,
+            ended_early: false,
+            length: Some(SessionLength::Questions(10)),
+        }
+    }
+
+    #[test]
+    fn personal_best_has_most_correct_then_quickest() {
+        let history = History {
+            sessions: vec![
+                session(8, 30),
+                session(9, 60),
+                session(9, 45),
+                session(7, 10),
+            ],
+        };
+        let best = history
+            .personal_best(
+                EquationDifficulty::Simple,
+                GameMode::Standard,
+                SessionLength::Questions(10),
+            )
+            .unwrap();
+        assert_eq!((best.equations_success, best.time_taken.as_secs()), (9, 45));
+        assert!(
+            history
+                .personal_best(
+                    EquationDifficulty::Medium,
+                    GameMode::Standard,
+                    SessionLength::Questions(10)
+                )
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn personal_best_only_compares_finished_sessions_of_the_same_length() {
+        let longer = SessionRecord {
+            equations_completed: 20,
+            length: Some(SessionLength::Questions(20)),
+            ..session(15, 90)
+        };
+        let ended_early = SessionRecord {
+            ended_early: true,
+            ..session(0, 1)
+        };
+        let history = History {
+            sessions: vec![session(6, 30), longer, ended_early],
+        };
+        assert_eq!(
+            history.session_lengths(EquationDifficulty::Simple, GameMode::Standard),
+            [SessionLength::Questions(10), SessionLength::Questions(20)]
+        );
+        let best = history
+            .personal_best(
+                EquationDifficulty::Simple,
+                GameMode::Standard,
+                SessionLength::Questions(10),
+            )
+            .unwrap();
+        assert_eq!((best.equations_success, best.time_taken.as_secs()), (6, 30));
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(1_792_281_600), "2026-10-18");
+    }
+}
@@ -1,27 +1,68 @@
 use std::fmt::Display;
 // use iced::time::{Duration,Instant};
-use wasm_timer::Instant;
 use std::time::Duration;
+use wasm_timer::Instant;
 
+use adaptive::AdaptiveDifficulty;
 use equation::{
-    Equation,
-    medium::{new_complex_equation, new_medium_equation},
-    simple::new_simple_equation,
+    Question,
+    answer::AnswerParseError,
+    config::{GeneratorConfig, OperatorWeights},
+    medium::new_configured_equation,
+    pack::{ImportedPack, PackError, QuestionPack},
+    practice::{PracticeOptions, PracticeSet},
+    saved::{LoadError, SavedQuestion},
 };
+use history::{History, SessionLength, SessionRecord, current_date, format_date};
 use iced::{
-    Background, Color, Element, Font, Length, Padding, Subscription,
+    Background, Color, Element, Font, Length, Padding, Subscription, Task,
+    alignment::Vertical,
+    clipboard,
     font::Weight,
     gradient::Linear,
     time,
-    widget::{Button, button, column, container, row, text, text_input},
+    widget::{
+        Button, button, checkbox, column, container, pick_list, row, scrollable, text, text_input,
+    },
 };
+use math_view::math_view;
+use rand::SeedableRng;
+use resume::SavedSession;
+use review::ReviewDeck;
+use serde::{Deserialize, Serialize};
+use worksheet::Worksheet;
 
+mod adaptive;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
 pub mod equation;
+mod history;
+mod math_view;
+mod resume;
+mod review;
+mod storage;
+mod worksheet;
 
 fn main() -> iced::Result {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        if !args.is_empty() {
+            std::process::exit(cli::run(&args));
+        }
+    }
+
     iced::application("Number Pain", update, view)
         .subscription(subscription)
-        .run()
+        .run_with(|| {
+            let model = Model {
+                history: History::load(),
+                review_deck: ReviewDeck::load(),
+                saved_session: SavedSession::load(),
+                ..Default::default()
+            };
+            (model, Task::none())
+        })
 }
 
 /// The state of the program
@@ -29,23 +70,138 @@ fn main() -> iced::Result {
 struct Model {
     program_state: ProgramState,
     timer: Timer,
+    settings: SessionSettings,
+    history: History,
+    review_deck: ReviewDeck,
+    /// A session which was left unfinished, to be resumed from the main menu
+    saved_session: Option<SavedSession>,
+    /// The pack played in [`GameMode::Pack`], pasted in on the main menu
+    question_pack: Option<ImportedPack>,
 }
 
-#[derive(Debug)]
-struct Timer {
-    time_taken: Duration,
-    last_tick: Option<Instant>,
+/// How a session of questions is set up, picked on the main menu
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct SessionSettings {
+    game_mode: GameMode,
+    equation_difficulty: EquationDifficulty,
+    number_equations: u32,
+    time_limit: TimeLimit,
+    sprint_length: SprintLength,
+    /// Used for [`EquationDifficulty::Custom`]
+    custom_config: GeneratorConfig,
+    /// Used for [`EquationDifficulty::Practice`]
+    practice_options: PracticeOptions,
 }
 
-impl Default for Timer {
+impl Default for SessionSettings {
     fn default() -> Self {
         Self {
-            time_taken: Default::default(),
-            last_tick: Default::default(),
+            game_mode: GameMode::Standard,
+            equation_difficulty: EquationDifficulty::Simple,
+            number_equations: 10,
+            time_limit: TimeLimit(None),
+            sprint_length: SprintLength(Duration::from_secs(60)),
+            custom_config: GeneratorConfig::default(),
+            practice_options: PracticeOptions::default(),
+        }
+    }
+}
+
+impl SessionSettings {
+    /// The number of questions before the session finishes, sprints keep going until the time
+    /// runs out
+    fn question_limit(&self) -> Option<u32> {
+        match self.game_mode {
+            GameMode::Standard | GameMode::Review | GameMode::Pack => Some(self.number_equations),
+            GameMode::Sprint => None,
+        }
+    }
+
+    /// What the session is set to last for, used to compare it with others in the history
+    fn session_length(&self) -> SessionLength {
+        match (self.question_limit(), self.countdown()) {
+            (Some(questions), _) => SessionLength::Questions(questions),
+            (None, time) => SessionLength::Time(time.unwrap_or_default()),
+        }
+    }
+
+    /// How long the session can go on for, if there's a limit
+    fn countdown(&self) -> Option<Duration> {
+        match self.game_mode {
+            GameMode::Standard | GameMode::Review | GameMode::Pack => self.time_limit.0,
+            GameMode::Sprint => Some(self.sprint_length.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum GameMode {
+    /// A set number of questions
+    Standard,
+    /// As many questions as possible before the time runs out
+    Sprint,
+    /// Questions missed before which are due for review, then new ones to make up the number
+    Review,
+    /// Every question from a pack in order
+    Pack,
+}
+
+impl Display for GameMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let string = match self {
+            GameMode::Standard => "Standard",
+            GameMode::Sprint => "Sprint",
+            GameMode::Review => "Review mistakes",
+            GameMode::Pack => "Question pack",
+        };
+        write!(f, "{}", string)
+    }
+}
+
+const NUMBER_EQUATIONS_OPTIONS: [u32; 5] = [5, 10, 20, 30, 50];
+
+const TIME_LIMIT_OPTIONS: [TimeLimit; 4] = [
+    TimeLimit(None),
+    TimeLimit(Some(Duration::from_secs(60))),
+    TimeLimit(Some(Duration::from_secs(2 * 60))),
+    TimeLimit(Some(Duration::from_secs(5 * 60))),
+];
+
+const SPRINT_LENGTH_OPTIONS: [SprintLength; 4] = [
+    SprintLength(Duration::from_secs(30)),
+    SprintLength(Duration::from_secs(60)),
+    SprintLength(Duration::from_secs(2 * 60)),
+    SprintLength(Duration::from_secs(5 * 60)),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct SprintLength(Duration);
+
+impl Display for SprintLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} seconds", self.0.as_secs())
+    }
+}
+
+/// An optional limit on how long a session can take
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct TimeLimit(Option<Duration>);
+
+impl Display for TimeLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(duration) => write!(f, "{} min", duration.as_secs() / 60),
+            None => write!(f, "No limit"),
         }
     }
 }
 
+#[derive(Debug, Default)]
+struct Timer {
+    time_taken: Duration,
+    last_tick: Option<Instant>,
+}
+
 impl Timer {
     fn add_instant(&mut self, current_tick: Instant) {
         if let Some(last_tick) = self.last_tick {
@@ -57,67 +213,174 @@ impl Timer {
 
 #[derive(Debug)]
 enum ProgramState {
-    MainMenu(EquationDifficulty),
+    MainMenu(MainMenuState),
+    History,
     Equation(EquationModelState),
     FinishedEquation(EquationFinishedState),
 }
 
 impl Default for ProgramState {
     fn default() -> Self {
-        Self::MainMenu(EquationDifficulty::Simple)
+        Self::MainMenu(MainMenuState::default())
     }
 }
 
+/// The state of the main menu
+#[derive(Debug, Default)]
+struct MainMenuState {
+    /// The seed typed in to replay a previous set of questions, random if empty
+    seed_text: String,
+    /// Why the last pasted question pack couldn't be used
+    pack_error: Option<PackError>,
+    /// Where the last worksheet was saved, or why it couldn't be
+    worksheet_status: Option<String>,
+}
+
 #[derive(Debug)]
 struct EquationFinishedState {
     equation_progress: EquationProgress,
-    equation_difficulty: EquationDifficulty,
-    ended_early: bool,
+    settings: SessionSettings,
+    seed: u64,
+    finish_reason: FinishReason,
+    /// How long the session went on for
+    time_taken: Duration,
     background_colors: Vec<Color>,
+    /// What happened copying the questions as a pack
+    pack_status: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FinishReason {
+    Completed,
+    EndedEarly,
+    OutOfTime,
 }
 
 /// The state of the equation model
 #[derive(Debug)]
 struct EquationModelState {
-    current_equation: Box<dyn Equation>,
-    equation_difficulty: EquationDifficulty,
+    current_equation: Box<dyn Question>,
+    settings: SessionSettings,
+    /// The time on the [`Timer`] when the session started, or was resumed
+    start_time: Duration,
+    /// How long the session had gone on for before it was resumed
+    time_before_resume: Duration,
+    /// The time on the [`Timer`] when the current question was shown
+    question_start_time: Duration,
+    /// The seed the questions are generated from so the session can be replayed
+    seed: u64,
+    rng: Box<SessionRng>,
     current_answer_text: String,
+    answer_error: Option<AnswerParseError>,
     equation_progress: EquationProgress,
+    /// Only used for [`EquationDifficulty::Adaptive`]
+    adaptive_difficulty: AdaptiveDifficulty,
+    /// Questions to ask before generating any, like missed ones or a pack's, the next one last
+    queued_questions: Vec<SavedQuestion>,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Generates a session's questions. The same as `StdRng` but it can be saved along with the
+/// session
+type SessionRng = rand_chacha::ChaCha12Rng;
+
+/// How many columns worksheets saved from the main menu have
+const WORKSHEET_COLUMNS: usize = 2;
+
+/// The share of questions adaptive difficulty aims for the player to get right
+const ADAPTIVE_TARGET_SUCCESS_RATE: f32 = 0.8;
+
+impl EquationModelState {
+    /// How long the session has gone on for when the [`Timer`] is at `time_taken`
+    fn elapsed(&self, time_taken: Duration) -> Duration {
+        time_taken.saturating_sub(self.start_time) + self.time_before_resume
+    }
+
+    /// The time left before the session ends, if it has a time limit
+    fn time_remaining(&self, time_taken: Duration) -> Option<Duration> {
+        let elapsed = self.elapsed(time_taken);
+        self.settings
+            .countdown()
+            .map(|countdown| countdown.saturating_sub(elapsed))
+    }
+}
+
+#[derive(Debug, Default)]
 struct EquationProgress {
-    number_equations: u32,
+    /// `None` if the session only ends when the time runs out
+    number_equations: Option<u32>,
     equations_success: u32,
     equations_completed: u32,
+    /// The questions answered so far, in order
+    question_records: Vec<QuestionRecord>,
+}
+
+/// A question that was answered and how it went
+#[derive(Debug)]
+struct QuestionRecord {
+    equation: Box<dyn Question>,
+    /// The correct answer written the way the player would type it
+    correct_answer: String,
+    submitted_text: String,
+    correct: bool,
+    /// How long it took to answer
+    time_taken: Duration,
 }
 
 impl EquationProgress {
-    fn new(number: u32) -> Self {
+    fn new(number: Option<u32>) -> Self {
         Self {
             number_equations: number,
-            equations_success: 0,
-            equations_completed: 0,
+            ..Default::default()
         }
     }
 
-    fn add_equation(&mut self, success: bool) {
+    fn add_equation(&mut self, question_record: QuestionRecord) {
         self.equations_completed += 1;
-        if success {
+        if question_record.correct {
             self.equations_success += 1;
         }
+        self.question_records.push(question_record);
     }
 
     fn completed(&self) -> bool {
-        self.equations_completed >= self.number_equations
+        self.number_equations
+            .is_some_and(|number| self.equations_completed >= number)
+    }
+
+    /// The questions answered per minute over `time_taken`, or `None` if it took less than a
+    /// second since the rate would be meaningless
+    fn questions_per_minute(&self, time_taken: Duration) -> Option<f32> {
+        (time_taken >= Duration::from_secs(1))
+            .then(|| self.equations_completed as f32 / (time_taken.as_secs_f32() / 60.0))
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 enum EquationDifficulty {
     Simple,
     Medium,
     Complex,
+    /// Questions get harder or easier depending on how the player is doing
+    Adaptive,
+    /// Questions generated with the player's own settings
+    Custom,
+    /// Questions drilling one skill
+    Practice(PracticeSet),
+}
+
+impl EquationDifficulty {
+    /// Every difficulty including the practice sets
+    fn all() -> impl Iterator<Item = EquationDifficulty> {
+        [
+            EquationDifficulty::Simple,
+            EquationDifficulty::Medium,
+            EquationDifficulty::Complex,
+            EquationDifficulty::Adaptive,
+            EquationDifficulty::Custom,
+        ]
+        .into_iter()
+        .chain(PracticeSet::ALL.map(EquationDifficulty::Practice))
+    }
 }
 
 impl Display for EquationDifficulty {
@@ -126,121 +389,463 @@ impl Display for EquationDifficulty {
             EquationDifficulty::Simple => "Simple",
             EquationDifficulty::Medium => "Medium",
             EquationDifficulty::Complex => "Complex",
+            EquationDifficulty::Adaptive => "Adaptive",
+            EquationDifficulty::Custom => "Custom",
+            EquationDifficulty::Practice(practice_set) => return write!(f, "{}", practice_set),
         };
         write!(f, "{}", string)
     }
 }
 
-fn new_equation_model_state(equation_difficulty: EquationDifficulty) -> EquationModelState {
-    let equation: Box<dyn Equation> = new_equation(equation_difficulty);
-
-    let num_equations = 10;
+/// Starts a session which asks `queued_questions` in order before generating any
+fn new_equation_model_state(
+    mut settings: SessionSettings,
+    seed: u64,
+    start_time: Duration,
+    mut queued_questions: Vec<SavedQuestion>,
+) -> EquationModelState {
+    let mut rng = Box::new(SessionRng::seed_from_u64(seed));
+    let adaptive_difficulty = AdaptiveDifficulty::new(ADAPTIVE_TARGET_SUCCESS_RATE);
+    if settings.game_mode == GameMode::Pack {
+        settings.number_equations = queued_questions.len() as u32;
+    }
+    queued_questions.reverse();
+    let equation = next_equation(
+        &settings,
+        &adaptive_difficulty,
+        &mut rng,
+        &mut queued_questions,
+    );
 
     EquationModelState {
         current_equation: equation,
-        equation_difficulty,
+        settings,
+        start_time,
+        time_before_resume: Duration::ZERO,
+        question_start_time: start_time,
+        seed,
+        rng,
         current_answer_text: String::new(),
-        equation_progress: EquationProgress::new(num_equations),
+        answer_error: None,
+        equation_progress: EquationProgress::new(settings.question_limit()),
+        adaptive_difficulty,
+        queued_questions,
+    }
+}
+
+fn finish_equations(
+    equation_state: &mut EquationModelState,
+    finish_reason: FinishReason,
+    time_taken: Duration,
+) -> EquationFinishedState {
+    EquationFinishedState {
+        time_taken: equation_state.elapsed(time_taken),
+        equation_progress: std::mem::take(&mut equation_state.equation_progress),
+        settings: equation_state.settings,
+        seed: equation_state.seed,
+        finish_reason,
+        background_colors: generate_finish_menu_colors(),
+        pack_status: None,
+    }
+}
+
+/// Whether a session in the game mode has anything to ask. A pack has to be pasted in first,
+/// which it won't be after resuming a pack session from a restart
+fn can_play(game_mode: GameMode, model: &Model) -> bool {
+    game_mode != GameMode::Pack || model.question_pack.is_some()
+}
+
+/// The questions asked first in a game mode, the ones due for review or the pasted pack's
+fn queued_questions(game_mode: GameMode, model: &Model) -> Vec<SavedQuestion> {
+    match game_mode {
+        GameMode::Review => model.review_deck.due_questions(current_date()),
+        GameMode::Pack => model
+            .question_pack
+            .as_ref()
+            .map(|question_pack| question_pack.questions.clone())
+            .unwrap_or_default(),
+        GameMode::Standard | GameMode::Sprint => Vec::new(),
     }
 }
 
-fn new_equation(equation_difficulty: EquationDifficulty) -> Box<dyn Equation> {
-    match equation_difficulty {
-        EquationDifficulty::Simple => Box::new(new_simple_equation()),
-        EquationDifficulty::Medium => Box::new(new_medium_equation()),
-        EquationDifficulty::Complex => Box::new(new_complex_equation()),
+/// Starts playing a session, saving it straight away so it can be resumed
+fn start_session(model: &mut Model, equation_state: EquationModelState) {
+    save_session(&equation_state, model.timer.time_taken);
+    model.saved_session = None;
+    model.program_state = ProgramState::Equation(equation_state);
+}
+
+fn save_session(equation_state: &EquationModelState, time_taken: Duration) {
+    if let Some(saved_session) = SavedSession::new(equation_state, time_taken) {
+        saved_session.save();
     }
 }
 
-fn update(model: &mut Model, message: Message) {
+/// Shows the results of a finished session and saves them to the history
+fn show_finished(model: &mut Model, equation_finished: EquationFinishedState) {
+    SavedSession::clear();
+    // a session ended before answering anything has nothing worth keeping
+    if equation_finished.equation_progress.equations_completed == 0 {
+        model.program_state = ProgramState::FinishedEquation(equation_finished);
+        return;
+    }
+    model.history.add_session(SessionRecord {
+        game_mode: equation_finished.settings.game_mode,
+        equation_difficulty: equation_finished.settings.equation_difficulty,
+        equations_success: equation_finished.equation_progress.equations_success,
+        equations_completed: equation_finished.equation_progress.equations_completed,
+        time_taken: equation_finished.time_taken,
+        date: current_date(),
+        ended_early: equation_finished.finish_reason == FinishReason::EndedEarly,
+        length: Some(equation_finished.settings.session_length()),
+    });
+    model.program_state = ProgramState::FinishedEquation(equation_finished);
+}
+
+/// The next missed question to review if there are any left, otherwise a new one
+fn next_equation(
+    settings: &SessionSettings,
+    adaptive_difficulty: &AdaptiveDifficulty,
+    rng: &mut SessionRng,
+    queued_questions: &mut Vec<SavedQuestion>,
+) -> Box<dyn Question> {
+    match queued_questions.pop() {
+        Some(question) => question.into_question(),
+        None => new_equation(settings, adaptive_difficulty, rng),
+    }
+}
+
+fn new_equation(
+    settings: &SessionSettings,
+    adaptive_difficulty: &AdaptiveDifficulty,
+    rng: &mut SessionRng,
+) -> Box<dyn Question> {
+    let config = match settings.equation_difficulty {
+        EquationDifficulty::Simple => GeneratorConfig::SIMPLE,
+        EquationDifficulty::Medium => GeneratorConfig::MEDIUM,
+        EquationDifficulty::Complex => GeneratorConfig::COMPLEX,
+        EquationDifficulty::Adaptive => adaptive_difficulty.generator_config(),
+        EquationDifficulty::Custom => settings.custom_config,
+        EquationDifficulty::Practice(practice_set) => {
+            return practice_set.new_equation(rng, &settings.practice_options);
+        }
+    };
+    Box::new(new_configured_equation(rng, &config))
+}
+
+fn update(model: &mut Model, message: Message) -> Task<Message> {
     match message {
         Message::StartGame => match &model.program_state {
-            ProgramState::MainMenu(equation_difficulty) => {
-                model.program_state =
-                    ProgramState::Equation(new_equation_model_state(*equation_difficulty))
+            ProgramState::MainMenu(_) if !can_play(model.settings.game_mode, model) => {}
+            ProgramState::MainMenu(main_menu_state) => {
+                let seed = main_menu_state
+                    .seed_text
+                    .parse()
+                    .unwrap_or_else(|_| rand::random());
+                let equation_state = new_equation_model_state(
+                    model.settings,
+                    seed,
+                    model.timer.time_taken,
+                    queued_questions(model.settings.game_mode, model),
+                );
+                start_session(model, equation_state);
             }
-            ProgramState::FinishedEquation(finished_equation_state) => {
-                model.program_state = ProgramState::Equation(new_equation_model_state(
-                    finished_equation_state.equation_difficulty,
-                ))
+            ProgramState::FinishedEquation(finished_equation_state)
+                if can_play(finished_equation_state.settings.game_mode, model) =>
+            {
+                let equation_state = new_equation_model_state(
+                    finished_equation_state.settings,
+                    rand::random(),
+                    model.timer.time_taken,
+                    queued_questions(finished_equation_state.settings.game_mode, model),
+                );
+                start_session(model, equation_state);
             }
             _ => {}
         },
+        Message::ReplayGame => {
+            if let ProgramState::FinishedEquation(finished_equation_state) = &model.program_state
+                && can_play(finished_equation_state.settings.game_mode, model)
+            {
+                let equation_state = new_equation_model_state(
+                    finished_equation_state.settings,
+                    finished_equation_state.seed,
+                    model.timer.time_taken,
+                    queued_questions(finished_equation_state.settings.game_mode, model),
+                );
+                start_session(model, equation_state);
+            }
+        }
+        Message::ResumeGame => {
+            if let Some(saved_session) = model.saved_session.take() {
+                model.program_state =
+                    ProgramState::Equation(saved_session.into_state(model.timer.time_taken));
+            }
+        }
+        Message::ChangeSeed(seed_text) => {
+            if let ProgramState::MainMenu(main_menu_state) = &mut model.program_state {
+                // only allow text that can be used as a seed
+                if seed_text.is_empty() || seed_text.parse::<u64>().is_ok() {
+                    main_menu_state.seed_text = seed_text;
+                }
+            }
+        }
         Message::ChangeAnswer(new_answer) => {
             if let ProgramState::Equation(equation_state) = &mut model.program_state {
                 equation_state.current_answer_text = new_answer;
+                equation_state.answer_error = None;
             }
         }
         Message::SubmitAnswer => {
             if let ProgramState::Equation(equation_state) = &mut model.program_state {
-                let value_parsed = equation_state.current_answer_text.parse::<f64>();
-                let value_correct = if let Ok(value) = value_parsed {
-                    equation_state.current_equation.compare_value(value)
-                } else {
-                    false
+                let correct = match equation_state
+                    .current_equation
+                    .check_answer(&equation_state.current_answer_text)
+                {
+                    Ok(correct) => correct,
+                    Err(err) => {
+                        // let the player fix the typo rather than marking it wrong
+                        equation_state.answer_error = Some(err);
+                        return Task::none();
+                    }
                 };
 
-                equation_state.equation_progress.add_equation(value_correct);
-                equation_state.current_answer_text = String::new();
+                let time_taken = model.timer.time_taken;
+                let question_time = time_taken.saturating_sub(equation_state.question_start_time);
+                equation_state
+                    .adaptive_difficulty
+                    .record_answer(correct, question_time);
+                if let Some(question) = equation_state.current_equation.to_saved()
+                    && model
+                        .review_deck
+                        .record_answer(question, correct, current_date())
+                {
+                    model.review_deck.save();
+                }
+
+                let next_equation = next_equation(
+                    &equation_state.settings,
+                    &equation_state.adaptive_difficulty,
+                    &mut equation_state.rng,
+                    &mut equation_state.queued_questions,
+                );
+                let answered_equation =
+                    std::mem::replace(&mut equation_state.current_equation, next_equation);
+                equation_state
+                    .equation_progress
+                    .add_equation(QuestionRecord {
+                        correct_answer: answered_equation.answer_text(),
+                        equation: answered_equation,
+                        submitted_text: std::mem::take(&mut equation_state.current_answer_text),
+                        correct,
+                        time_taken: question_time,
+                    });
+                equation_state.question_start_time = time_taken;
 
                 if equation_state.equation_progress.completed() {
-                    let equation_finished = EquationFinishedState {
-                        equation_progress: equation_state.equation_progress.clone(),
-                        equation_difficulty: equation_state.equation_difficulty,
-                        ended_early: false,
-                        background_colors: generate_finish_menu_colors(),
-                    };
-                    model.program_state = ProgramState::FinishedEquation(equation_finished);
+                    let equation_finished = finish_equations(
+                        equation_state,
+                        FinishReason::Completed,
+                        model.timer.time_taken,
+                    );
+                    show_finished(model, equation_finished);
                 } else {
-                    equation_state.current_equation =
-                        new_equation(equation_state.equation_difficulty);
+                    save_session(equation_state, time_taken);
                 }
             }
         }
+        Message::PastePack => return clipboard::read().map(Message::PackPasted),
+        Message::PackPasted(pack_text) => {
+            let imported = pack_text
+                .ok_or(PackError::Load(LoadError::Json(
+                    "nothing to paste".to_string(),
+                )))
+                .and_then(|pack_text| QuestionPack::from_json(&pack_text))
+                .and_then(QuestionPack::import);
+            if let ProgramState::MainMenu(main_menu_state) = &mut model.program_state {
+                match imported {
+                    Ok(question_pack) => {
+                        model.question_pack = Some(question_pack);
+                        main_menu_state.pack_error = None;
+                    }
+                    Err(err) => main_menu_state.pack_error = Some(err),
+                }
+            }
+        }
+        Message::CopyPack => {
+            if let ProgramState::FinishedEquation(finished_state) = &mut model.program_state {
+                let (question_pack, skipped) = QuestionPack::from_questions(
+                    format!("{} questions", finished_state.settings.equation_difficulty),
+                    finished_state
+                        .equation_progress
+                        .question_records
+                        .iter()
+                        .map(|question_record| question_record.equation.as_ref()),
+                );
+                let copied = question_pack.questions.len();
+                finished_state.pack_status = Some(match skipped {
+                    0 => format!("Copied {} questions", copied),
+                    _ => format!(
+                        "Copied {} questions, {} couldn't be written as a pack",
+                        copied, skipped
+                    ),
+                });
+                return clipboard::write(question_pack.to_json());
+            }
+        }
+        Message::SaveWorksheet => {
+            if let ProgramState::MainMenu(main_menu_state) = &mut model.program_state {
+                let seed = main_menu_state
+                    .seed_text
+                    .parse()
+                    .unwrap_or_else(|_| rand::random());
+                let worksheet = Worksheet::new(
+                    &model.settings,
+                    model.settings.number_equations as usize,
+                    WORKSHEET_COLUMNS,
+                    seed,
+                );
+                let name = format!("number-pain-worksheet-{}", seed);
+                main_menu_state.worksheet_status =
+                    Some(worksheet::save(&worksheet, &name).unwrap_or_else(|err| err));
+            }
+        }
+        Message::ShowHistory => model.program_state = ProgramState::History,
         Message::ReturnMenu => {
-            model.program_state = ProgramState::MainMenu(EquationDifficulty::Simple)
+            model.program_state = ProgramState::MainMenu(MainMenuState::default())
+        }
+        Message::ChangeGameMode(game_mode) => model.settings.game_mode = game_mode,
+        Message::ChangeSprintLength(sprint_length) => model.settings.sprint_length = sprint_length,
+        Message::ChangeDifficulty(equation_difficulty) => {
+            model.settings.equation_difficulty = equation_difficulty
+        }
+        Message::ChangeCustomConfig(config) => model.settings.custom_config = config.validated(),
+        Message::ChangePracticeOptions(options) => model.settings.practice_options = options,
+        Message::ChangeNumberEquations(number_equations) => {
+            model.settings.number_equations = number_equations
         }
-        Message::ChangeDifficulty(equation_difficulty) => match model.program_state {
-            ProgramState::MainMenu(_) => {
-                model.program_state = ProgramState::MainMenu(equation_difficulty)
+        Message::ChangeTimeLimit(time_limit) => model.settings.time_limit = time_limit,
+        Message::CancelEquation => {
+            if let ProgramState::Equation(equation_model_state) = &mut model.program_state {
+                let equation_finished = finish_equations(
+                    equation_model_state,
+                    FinishReason::EndedEarly,
+                    model.timer.time_taken,
+                );
+                show_finished(model, equation_finished);
             }
-            _ => {}
-        },
-        Message::CancelEquation => match &model.program_state {
-            ProgramState::Equation(equation_model_state) => {
-                let equation_finished = EquationFinishedState {
-                    equation_progress: equation_model_state.equation_progress.clone(),
-                    equation_difficulty: equation_model_state.equation_difficulty,
-                    ended_early: true,
-                    background_colors: generate_finish_menu_colors(),
-                };
-                model.program_state = ProgramState::FinishedEquation(equation_finished);
+        }
+        Message::CopyMathml => {
+            if let ProgramState::Equation(equation_state) = &model.program_state {
+                return clipboard::write(equation_state.current_equation.to_mathml());
             }
-            _ => {}
-        },
+        }
+        Message::CopyLatex(latex) => return clipboard::write(latex),
         Message::Tick(now) => {
             model.timer.add_instant(now);
+            if let ProgramState::Equation(equation_state) = &mut model.program_state
+                && equation_state.time_remaining(model.timer.time_taken) == Some(Duration::ZERO)
+            {
+                let equation_finished = finish_equations(
+                    equation_state,
+                    FinishReason::OutOfTime,
+                    model.timer.time_taken,
+                );
+                show_finished(model, equation_finished);
+            }
         }
     }
+    Task::none()
 }
 
-fn view(model: &Model) -> Element<Message> {
+fn view(model: &Model) -> Element<'_, Message> {
     match &model.program_state {
-        ProgramState::MainMenu(equation_difficulty) => container(column![
+        ProgramState::MainMenu(main_menu_state) => container(column![
             container(column![
                 text("Number Pain").size(50),
                 text("Test your numerical calculation skills!").size(20)
             ])
             .center_x(Length::Fill),
             container(row![
-                difficulty_button(EquationDifficulty::Simple, *equation_difficulty),
-                difficulty_button(EquationDifficulty::Medium, *equation_difficulty),
-                difficulty_button(EquationDifficulty::Complex, *equation_difficulty),
+                game_mode_button(GameMode::Standard, model.settings.game_mode),
+                game_mode_button(GameMode::Sprint, model.settings.game_mode),
+                game_mode_button(GameMode::Review, model.settings.game_mode),
+                game_mode_button(GameMode::Pack, model.settings.game_mode),
+            ])
+            .center_x(Length::Fill)
+            .padding(Padding::from(10)),
+            container(review_status(&model.settings, &model.review_deck)).center_x(Length::Fill),
+            container(pack_status(model, main_menu_state)).center_x(Length::Fill),
+            container(row![
+                difficulty_button(
+                    EquationDifficulty::Simple,
+                    model.settings.equation_difficulty
+                ),
+                difficulty_button(
+                    EquationDifficulty::Medium,
+                    model.settings.equation_difficulty
+                ),
+                difficulty_button(
+                    EquationDifficulty::Complex,
+                    model.settings.equation_difficulty
+                ),
+                difficulty_button(
+                    EquationDifficulty::Adaptive,
+                    model.settings.equation_difficulty
+                ),
+                difficulty_button(
+                    EquationDifficulty::Custom,
+                    model.settings.equation_difficulty
+                ),
             ])
             .center_x(Length::Fill)
             .padding(Padding::from(10)),
-            container(button(text("Start game")).on_press(Message::StartGame))
-                .center_x(Length::Fill),
+            container(
+                // a few to a row so they fit across the window
+                column(PracticeSet::ALL.chunks(4).map(|practice_sets| {
+                    row(practice_sets.iter().map(|practice_set| {
+                        difficulty_button(
+                            EquationDifficulty::Practice(*practice_set),
+                            model.settings.equation_difficulty,
+                        )
+                        .into()
+                    }))
+                    .spacing(5)
+                    .into()
+                }))
+                .spacing(5)
+            )
+            .center_x(Length::Fill),
+            container(practice_options_editor(&model.settings)).center_x(Length::Fill),
+            container(custom_config_editor(&model.settings)).center_x(Length::Fill),
+            container(session_length_options(&model.settings))
+                .center_x(Length::Fill)
+                .padding(Padding::from(10)),
+            container(
+                text_input("Seed (optional)", &main_menu_state.seed_text)
+                    .on_input(Message::ChangeSeed)
+                    .on_submit(Message::StartGame)
+                    .width(200)
+            )
+            .center_x(Length::Fill)
+            .padding(Padding::from(10)),
+            container(
+                row![
+                    button(text("Start game")).on_press_maybe(
+                        can_play(model.settings.game_mode, model).then_some(Message::StartGame)
+                    ),
+                    button(text("Resume last session"))
+                        .on_press_maybe(model.saved_session.as_ref().map(|_| Message::ResumeGame)),
+                    button(text("Save worksheet")).on_press(Message::SaveWorksheet),
+                    button(text("History")).on_press(Message::ShowHistory),
+                ]
+                .spacing(10)
+            )
+            .center_x(Length::Fill),
+            container(text(
+                main_menu_state.worksheet_status.clone().unwrap_or_default()
+            ))
+            .center_x(Length::Fill),
         ])
         .style(|_theme| {
             container::Style::default()
@@ -249,24 +854,79 @@ fn view(model: &Model) -> Element<Message> {
         .center_x(Length::Fill)
         .center_y(Length::Fill)
         .into(),
+        ProgramState::History => container(
+            column![
+                text("History").size(35),
+                personal_bests(&model.history),
+                past_sessions(&model.history),
+                button("Return to start menu").on_press(Message::ReturnMenu),
+            ]
+            .spacing(10)
+            .max_width(500),
+        )
+        .style(|_theme| {
+            container::Style::default()
+                .background(start_menu_background(model.timer.time_taken.as_secs_f32()))
+        })
+        .center(Length::Fill)
+        .into(),
         ProgramState::Equation(equation_state) => container(column![
             // main
             container(
                 container(
                     column![
+                        container(
+                            text(
+                                equation_state
+                                    .time_remaining(model.timer.time_taken)
+                                    .map(|time_remaining| format!(
+                                        "{}:{:02}",
+                                        time_remaining.as_secs() / 60,
+                                        time_remaining.as_secs() % 60
+                                    ))
+                                    .unwrap_or_default()
+                            )
+                            .size(25)
+                        )
+                        .center_x(Length::Fill),
                         container(text("What's the answer?").size(30)).center_x(Length::Fill),
-                        container(text(format!("{}", equation_state.current_equation)).size(25))
-                            .center_x(Length::Fill),
                         container(
-                            text_input("Answer", &equation_state.current_answer_text)
-                                .on_input(Message::ChangeAnswer)
-                                .on_submit(Message::SubmitAnswer)
-                                .size(20)
-                                .padding(5)
+                            math_view(equation_state.current_equation.math_layout()).size(25)
+                        )
+                        .center_x(Length::Fill),
+                        container(
+                            text_input(
+                                equation_state.current_equation.answer_hint(),
+                                &equation_state.current_answer_text
+                            )
+                            .on_input(Message::ChangeAnswer)
+                            .on_submit(Message::SubmitAnswer)
+                            .size(20)
+                            .padding(5)
                         )
                         .center_x(Length::Shrink),
-                        container(button(text("Submit")).on_press(Message::SubmitAnswer))
-                            .center_x(Length::Fill)
+                        container(
+                            text(
+                                equation_state
+                                    .answer_error
+                                    .as_ref()
+                                    .map(|err| err.to_string())
+                                    .unwrap_or_default()
+                            )
+                            .color(Color::from_rgb(0.9, 0.3, 0.3))
+                        )
+                        .center_x(Length::Fill),
+                        container(
+                            row![
+                                button(text("Submit")).on_press(Message::SubmitAnswer),
+                                button(text("Copy as MathML")).on_press(Message::CopyMathml),
+                                button(text("Copy as LaTeX")).on_press(Message::CopyLatex(
+                                    equation_state.current_equation.to_latex()
+                                )),
+                            ]
+                            .spacing(10)
+                        )
+                        .center_x(Length::Fill)
                     ]
                     .spacing(20)
                 )
@@ -275,37 +935,62 @@ fn view(model: &Model) -> Element<Message> {
             .center_x(Length::Fill)
             .center_y(Length::Fill),
             container(column![
-                text(format!(
-                    "Completed {}/{}",
-                    equation_state.equation_progress.equations_completed,
-                    equation_state.equation_progress.number_equations
-                )),
+                text(match equation_state.equation_progress.number_equations {
+                    Some(number_equations) => format!(
+                        "Completed {}/{}",
+                        equation_state.equation_progress.equations_completed, number_equations
+                    ),
+                    None => format!(
+                        "Completed {}",
+                        equation_state.equation_progress.equations_completed
+                    ),
+                }),
+                text(adaptive_status(equation_state)),
                 container(button("End early").on_press(Message::CancelEquation))
             ])
             .align_right(Length::Fill)
         ])
         .into(),
         ProgramState::FinishedEquation(finished_state) => {
-            let header_text = if !finished_state.ended_early {
-                "Finished!"
-            } else {
-                "Ended"
+            let header_text = match finished_state.finish_reason {
+                FinishReason::Completed => "Finished!",
+                FinishReason::EndedEarly => "Ended",
+                FinishReason::OutOfTime => "Time's up!",
             };
             let score_text = format!(
                 "Scored {}/{}",
                 finished_state.equation_progress.equations_success,
                 finished_state.equation_progress.equations_completed
             );
+            let rate_text = match finished_state
+                .equation_progress
+                .questions_per_minute(finished_state.time_taken)
+            {
+                Some(rate) => format!("{:.1} questions per minute", rate),
+                None => "Too quick to work out a rate".to_string(),
+            };
             container(
                 container(
                     column![
                         text(header_text).size(35),
                         text(score_text).size(20),
+                        text(rate_text),
+                        text(format!("Seed {}", finished_state.seed)),
+                        question_review(&finished_state.equation_progress.question_records),
                         row![
-                            button("Start again").on_press(Message::StartGame),
+                            button("Start again").on_press_maybe(
+                                can_play(finished_state.settings.game_mode, model)
+                                    .then_some(Message::StartGame)
+                            ),
+                            button("Replay questions").on_press_maybe(
+                                can_play(finished_state.settings.game_mode, model)
+                                    .then_some(Message::ReplayGame)
+                            ),
                             button("Return to start menu").on_press(Message::ReturnMenu)
                         ]
-                        .spacing(20)
+                        .spacing(20),
+                        button("Copy as question pack").on_press(Message::CopyPack),
+                        text(finished_state.pack_status.clone().unwrap_or_default()),
                     ]
                     .spacing(10),
                 )
@@ -323,6 +1008,356 @@ fn view(model: &Model) -> Element<Message> {
     }
 }
 
+/// The best session for each difficulty and game mode that's been played
+fn personal_bests(history: &History) -> Element<'_, Message> {
+    let bests = EquationDifficulty::all().flat_map(|difficulty| {
+        [GameMode::Standard, GameMode::Sprint]
+            .into_iter()
+            .flat_map(move |game_mode| {
+                history
+                    .session_lengths(difficulty, game_mode)
+                    .into_iter()
+                    .filter_map(move |length| {
+                        let best = history.personal_best(difficulty, game_mode, length)?;
+                        Some(
+                            text(format!(
+                                "{} {}, {}: {}",
+                                difficulty,
+                                game_mode,
+                                length,
+                                session_summary(best)
+                            ))
+                            .into(),
+                        )
+                    })
+            })
+    });
+    column![text("Personal bests").size(20), column(bests).spacing(5)]
+        .spacing(5)
+        .into()
+}
+
+/// Every finished session, newest first
+fn past_sessions(history: &History) -> Element<'_, Message> {
+    let sessions = history.sessions.iter().rev().map(|session| {
+        text(format!(
+            "{}  {} {}  {}{}",
+            format_date(session.date),
+            session.equation_difficulty,
+            session.game_mode,
+            session_summary(session),
+            if session.ended_early {
+                " (ended early)"
+            } else {
+                ""
+            }
+        ))
+        .into()
+    });
+    column![
+        text("Past runs").size(20),
+        scrollable(column(sessions).spacing(5)).height(Length::Fixed(250.0)),
+    ]
+    .spacing(5)
+    .into()
+}
+
+fn session_summary(session: &SessionRecord) -> String {
+    let seconds = session.time_taken.as_secs();
+    format!(
+        "{}/{} in {}:{:02}",
+        session.equations_success,
+        session.equations_completed,
+        seconds / 60,
+        seconds % 60
+    )
+}
+
+/// The current level and recent accuracy when the difficulty is adaptive
+fn adaptive_status(equation_state: &EquationModelState) -> String {
+    if equation_state.settings.equation_difficulty != EquationDifficulty::Adaptive {
+        return String::new();
+    }
+    let adaptive_difficulty = &equation_state.adaptive_difficulty;
+    match adaptive_difficulty.recent_success_rate() {
+        Some(success_rate) => format!(
+            "Level {:.1}, {:.0}% right recently",
+            adaptive_difficulty.level(),
+            success_rate * 100.0
+        ),
+        None => format!("Level {:.1}", adaptive_difficulty.level()),
+    }
+}
+
+/// How many missed questions are waiting when reviewing mistakes
+fn review_status<'a>(settings: &SessionSettings, review_deck: &ReviewDeck) -> Element<'a, Message> {
+    if settings.game_mode != GameMode::Review {
+        return column![].into();
+    }
+    match review_deck.due_count(current_date()) {
+        0 => text("Nothing due for review, new questions will be asked").into(),
+        due => text(format!(
+            "{} missed questions due for review before new ones",
+            due
+        ))
+        .into(),
+    }
+}
+
+/// The pasted question pack when playing one, or why it couldn't be used
+fn pack_status<'a>(model: &Model, main_menu_state: &MainMenuState) -> Element<'a, Message> {
+    if model.settings.game_mode != GameMode::Pack {
+        return column![].into();
+    }
+    let status = match (&main_menu_state.pack_error, &model.question_pack) {
+        (Some(err), _) => text(err.to_string()).color(Color::from_rgb(0.9, 0.3, 0.3)),
+        (None, Some(question_pack)) => text(format!(
+            "{}, {} questions",
+            question_pack.pack.title,
+            question_pack.questions.len()
+        )),
+        (None, None) => text("Copy a question pack and paste it in to play it"),
+    };
+    row![
+        status,
+        button(text("Paste question pack")).on_press(Message::PastePack)
+    ]
+    .spacing(10)
+    .align_y(Vertical::Center)
+    .into()
+}
+
+/// Options for the selected practice set, if it has any
+fn practice_options_editor<'a>(settings: &SessionSettings) -> Element<'a, Message> {
+    match settings.equation_difficulty {
+        EquationDifficulty::Practice(practice_set) if practice_set.has_fraction_answers() => {
+            checkbox(
+                "Accept decimals",
+                settings.practice_options.fraction_decimals,
+            )
+            .on_toggle(|checked| {
+                Message::ChangePracticeOptions(PracticeOptions {
+                    fraction_decimals: checked,
+                })
+            })
+            .into()
+        }
+        _ => column![].into(),
+    }
+}
+
+/// Settings for generating questions when the difficulty is custom, every change sends the
+/// whole new config
+fn custom_config_editor<'a>(settings: &SessionSettings) -> Element<'a, Message> {
+    if settings.equation_difficulty != EquationDifficulty::Custom {
+        return column![].into();
+    }
+    let config = settings.custom_config;
+    let weights = config.operator_weights;
+    let operator_checkbox =
+        |label: &'a str, weight: u32, set_weight: fn(&mut OperatorWeights, u32)| {
+            checkbox(label, weight > 0).on_toggle(move |checked| {
+                let mut operator_weights = weights;
+                set_weight(&mut operator_weights, u32::from(checked));
+                Message::ChangeCustomConfig(GeneratorConfig {
+                    operator_weights,
+                    ..config
+                })
+            })
+        };
+    column![
+        row![
+            config_stepper(
+                "Depth from",
+                config.min_depth,
+                GeneratorConfig {
+                    min_depth: config.min_depth - 1,
+                    ..config
+                },
+                GeneratorConfig {
+                    min_depth: config.min_depth + 1,
+                    ..config
+                },
+            ),
+            config_stepper(
+                "to",
+                config.max_depth,
+                GeneratorConfig {
+                    max_depth: config.max_depth - 1,
+                    ..config
+                },
+                GeneratorConfig {
+                    max_depth: config.max_depth + 1,
+                    ..config
+                },
+            ),
+        ]
+        .spacing(20),
+        row![
+            config_stepper(
+                "Numbers from",
+                config.min_operand,
+                GeneratorConfig {
+                    min_operand: config.min_operand - 5,
+                    ..config
+                },
+                GeneratorConfig {
+                    min_operand: config.min_operand + 5,
+                    ..config
+                },
+            ),
+            config_stepper(
+                "to",
+                config.max_operand,
+                GeneratorConfig {
+                    max_operand: config.max_operand - 5,
+                    ..config
+                },
+                GeneratorConfig {
+                    max_operand: config.max_operand + 5,
+                    ..config
+                },
+            ),
+        ]
+        .spacing(20),
+        config_stepper(
+            "Decimal places",
+            config.decimal_places,
+            GeneratorConfig {
+                decimal_places: config.decimal_places.saturating_sub(1),
+                ..config
+            },
+            GeneratorConfig {
+                decimal_places: config.decimal_places + 1,
+                ..config
+            },
+        ),
+        row![
+            operator_checkbox("+", weights.add, |weights, weight| weights.add = weight),
+            operator_checkbox("-", weights.subtract, |weights, weight| weights.subtract =
+                weight),
+            operator_checkbox("×", weights.multiply, |weights, weight| weights.multiply =
+                weight),
+            operator_checkbox("÷", weights.divide, |weights, weight| weights.divide =
+                weight),
+        ]
+        .spacing(20),
+        row![
+            checkbox("Powers and roots", config.single_operator_chance > 0.0).on_toggle(
+                move |checked| Message::ChangeCustomConfig(GeneratorConfig {
+                    single_operator_chance: if checked { 0.25 } else { 0.0 },
+                    ..config
+                })
+            ),
+            checkbox("Negative numbers", config.allow_negatives).on_toggle(move |checked| {
+                Message::ChangeCustomConfig(GeneratorConfig {
+                    allow_negatives: checked,
+                    ..config
+                })
+            }),
+        ]
+        .spacing(20),
+    ]
+    .spacing(10)
+    .into()
+}
+
+/// A value in the custom config with buttons to move it down or up
+fn config_stepper<'a>(
+    label: &'a str,
+    value: impl Display,
+    decreased: GeneratorConfig,
+    increased: GeneratorConfig,
+) -> Element<'a, Message> {
+    row![
+        text(label),
+        button(text("-")).on_press(Message::ChangeCustomConfig(decreased)),
+        text(value.to_string()),
+        button(text("+")).on_press(Message::ChangeCustomConfig(increased)),
+    ]
+    .spacing(5)
+    .align_y(Vertical::Center)
+    .into()
+}
+
+/// The choices for how long a session goes on for, which depend on the game mode
+fn session_length_options<'a>(settings: &SessionSettings) -> Element<'a, Message> {
+    match settings.game_mode {
+        // a pack is always played all the way through
+        GameMode::Pack => row![
+            text("Time limit"),
+            pick_list(
+                TIME_LIMIT_OPTIONS,
+                Some(settings.time_limit),
+                Message::ChangeTimeLimit
+            ),
+        ],
+        GameMode::Standard | GameMode::Review => row![
+            text("Questions"),
+            pick_list(
+                NUMBER_EQUATIONS_OPTIONS,
+                Some(settings.number_equations),
+                Message::ChangeNumberEquations
+            ),
+            text("Time limit"),
+            pick_list(
+                TIME_LIMIT_OPTIONS,
+                Some(settings.time_limit),
+                Message::ChangeTimeLimit
+            ),
+        ],
+        GameMode::Sprint => row![
+            text("Sprint length"),
+            pick_list(
+                SPRINT_LENGTH_OPTIONS,
+                Some(settings.sprint_length),
+                Message::ChangeSprintLength
+            ),
+        ],
+    }
+    .spacing(10)
+    .align_y(Vertical::Center)
+    .into()
+}
+
+/// The questions from a finished session with the correct answers, each with a button to copy
+/// it out
+fn question_review(question_records: &[QuestionRecord]) -> Element<'_, Message> {
+    let questions = question_records.iter().map(|question_record| {
+        let answer_color = if question_record.correct {
+            Color::from_rgb(0.3, 0.9, 0.3)
+        } else {
+            Color::from_rgb(0.9, 0.3, 0.3)
+        };
+        row![
+            column![
+                math_view(question_record.equation.math_layout()),
+                text(format!("= {}", question_record.correct_answer)),
+                text(format!(
+                    "You answered {} in {:.1}s",
+                    question_record.submitted_text,
+                    question_record.time_taken.as_secs_f32()
+                ))
+                .size(12)
+                .color(answer_color),
+            ]
+            .width(Length::Fill),
+            button(text("Copy as LaTeX").size(12))
+                .on_press(Message::CopyLatex(question_record.equation.to_latex())),
+        ]
+        .spacing(10)
+        .align_y(Vertical::Center)
+        .into()
+    });
+    scrollable(
+        column(questions)
+            .spacing(10)
+            .padding(Padding::ZERO.right(15)),
+    )
+    .height(Length::Fixed(200.0))
+    .into()
+}
+
 fn start_menu_background(time_taken: f32) -> Background {
     Background::Gradient(iced::Gradient::Linear(
         Linear::new(time_taken * 0.1)
@@ -332,7 +1367,7 @@ fn start_menu_background(time_taken: f32) -> Background {
     ))
 }
 
-fn finish_menu_background(_time_taken: f32, colors: &Vec<Color>) -> Background {
+fn finish_menu_background(_time_taken: f32, colors: &[Color]) -> Background {
     let mut linear_gradient = Linear::new(std::f32::consts::FRAC_PI_3);
     let colors_len = (colors.len() - 1) as f32;
     for (index, color) in colors.iter().enumerate() {
@@ -359,14 +1394,31 @@ fn difficulty_button<'a>(
     difficulty: EquationDifficulty,
     current_difficulty: EquationDifficulty,
 ) -> Button<'a, Message> {
+    choice_button(
+        difficulty,
+        difficulty == current_difficulty,
+        Message::ChangeDifficulty(difficulty),
+    )
+}
+
+fn game_mode_button<'a>(game_mode: GameMode, current_game_mode: GameMode) -> Button<'a, Message> {
+    choice_button(
+        game_mode,
+        game_mode == current_game_mode,
+        Message::ChangeGameMode(game_mode),
+    )
+}
+
+/// A button for one of a set of choices, highlighted if it's the one picked
+fn choice_button<'a>(label: impl Display, selected: bool, message: Message) -> Button<'a, Message> {
     let font = Font {
         weight: Weight::Bold,
         ..Default::default()
     };
-    button(text(format!("{}", difficulty)).font(font))
-        .on_press(Message::ChangeDifficulty(difficulty))
+    button(text(format!("{}", label)).font(font))
+        .on_press(message)
         .style(move |_theme, _status| {
-            button::Style::default().with_background(if difficulty == current_difficulty {
+            button::Style::default().with_background(if selected {
                 Color::from_rgb(0.8, 0.2, 0.2)
             } else {
                 Color::from_rgb(0.5, 0.2, 0.2)
@@ -381,10 +1433,29 @@ fn subscription(_model: &Model) -> Subscription<Message> {
 #[derive(Debug, Clone)]
 enum Message {
     StartGame,
+    ReplayGame,
+    /// Carries on with the session which was left unfinished
+    ResumeGame,
+    ChangeSeed(String),
     ChangeAnswer(String),
     SubmitAnswer,
+    CopyMathml,
+    CopyLatex(String),
+    /// Copies the finished session's questions as a pack to share
+    CopyPack,
+    PastePack,
+    PackPasted(Option<String>),
+    /// Saves a worksheet of questions with the chosen settings to print out
+    SaveWorksheet,
     CancelEquation,
+    ChangeGameMode(GameMode),
+    ChangeSprintLength(SprintLength),
     ChangeDifficulty(EquationDifficulty),
+    ChangePracticeOptions(PracticeOptions),
+    ChangeCustomConfig(GeneratorConfig),
+    ChangeNumberEquations(u32),
+    ChangeTimeLimit(TimeLimit),
+    ShowHistory,
     ReturnMenu,
     Tick(Instant),
 }
@@ -0,0 +1,465 @@
+// custom widget drawing equations with stacked fractions, superscripts and radicals
+
+use iced::{
+    Element, Font, Length, Pixels, Point, Rectangle, Size,
+    advanced::{
+        Layout, Widget, layout, mouse,
+        renderer::{self, Quad},
+        text::{self, LineHeight, Paragraph, Shaping, Text, Wrapping},
+        widget::Tree,
+    },
+    alignment,
+};
+
+use crate::equation::layout::MathBox;
+
+/// A widget which draws a [`MathBox`]
+pub struct MathView {
+    math_box: MathBox,
+    size: f32,
+}
+
+pub fn math_view(math_box: MathBox) -> MathView {
+    MathView {
+        math_box,
+        size: 16.0,
+    }
+}
+
+impl MathView {
+    /// Sets the size of the text at the top level, superscripts are drawn smaller
+    pub fn size(mut self, size: impl Into<Pixels>) -> Self {
+        self.size = size.into().0;
+        self
+    }
+}
+
+/// A [`MathBox`] with the space it takes up worked out. Everything is positioned relative to a
+/// centre line so operators line up with fraction bars.
+struct Measured {
+    width: f32,
+    /// The height above the centre line
+    ascent: f32,
+    /// The height below the centre line
+    descent: f32,
+    content: MeasuredContent,
+}
+
+enum MeasuredContent {
+    Text {
+        content: String,
+        size: f32,
+    },
+    Row(Vec<Measured>),
+    Fraction(Box<Measured>, Box<Measured>),
+    Power(Box<Measured>, Box<Measured>),
+    Radical {
+        sign: Box<Measured>,
+        inner: Box<Measured>,
+    },
+    /// The index raised up beside a radical
+    Root {
+        index: Box<Measured>,
+        radical: Box<Measured>,
+    },
+    Brackets {
+        open: Box<Measured>,
+        inner: Box<Measured>,
+        close: Box<Measured>,
+    },
+    Absolute(Box<Measured>),
+}
+
+impl Measured {
+    fn height(&self) -> f32 {
+        self.ascent + self.descent
+    }
+}
+
+fn gap(size: f32) -> f32 {
+    size * 0.2
+}
+
+fn line_thickness(size: f32) -> f32 {
+    f32::max(1.0, size * 0.06)
+}
+
+fn measure<Renderer>(math_box: &MathBox, size: f32, font: Font) -> Measured
+where
+    Renderer: text::Renderer<Font = Font>,
+{
+    let gap = gap(size);
+    match math_box {
+        MathBox::Text(content) => measure_text::<Renderer>(content, size, font),
+        MathBox::Row(boxes) => {
+            let children: Vec<Measured> = boxes
+                .iter()
+                .map(|math_box| measure::<Renderer>(math_box, size, font))
+                .collect();
+            let spacing = gap * children.len().saturating_sub(1) as f32;
+            Measured {
+                width: children.iter().map(|child| child.width).sum::<f32>() + spacing,
+                ascent: children
+                    .iter()
+                    .map(|child| child.ascent)
+                    .fold(0.0, f32::max),
+                descent: children
+                    .iter()
+                    .map(|child| child.descent)
+                    .fold(0.0, f32::max),
+                content: MeasuredContent::Row(children),
+            }
+        }
+        MathBox::Fraction(numerator, denominator) => {
+            // nested fractions get smaller so they don't take over
+            let inner_size = f32::max(10.0, size * 0.9);
+            let numerator = measure::<Renderer>(numerator, inner_size, font);
+            let denominator = measure::<Renderer>(denominator, inner_size, font);
+            Measured {
+                width: f32::max(numerator.width, denominator.width) + gap * 2.0,
+                ascent: numerator.height() + gap * 0.5,
+                descent: denominator.height() + gap * 0.5,
+                content: MeasuredContent::Fraction(Box::new(numerator), Box::new(denominator)),
+            }
+        }
+        MathBox::Power(base, exponent) => {
+            let base = measure::<Renderer>(base, size, font);
+            let exponent = measure::<Renderer>(exponent, f32::max(8.0, size * 0.6), font);
+            let raise = exponent_raise(&base);
+            Measured {
+                width: base.width + exponent.width,
+                ascent: f32::max(base.ascent, raise + exponent.ascent),
+                descent: f32::max(base.descent, exponent.descent - raise),
+                content: MeasuredContent::Power(Box::new(base), Box::new(exponent)),
+            }
+        }
+        MathBox::Radical(inner) => {
+            let inner = measure::<Renderer>(inner, size, font);
+            let sign = measure_stretched::<Renderer>("√", inner.height() + gap, size, font);
+            Measured {
+                width: sign.width + inner.width + gap * 0.5,
+                ascent: f32::max(inner.ascent + gap, sign.ascent),
+                descent: f32::max(inner.descent, sign.descent),
+                content: MeasuredContent::Radical {
+                    sign: Box::new(sign),
+                    inner: Box::new(inner),
+                },
+            }
+        }
+        MathBox::Root(index, inner) => {
+            let index = measure::<Renderer>(index, f32::max(8.0, size * 0.5), font);
+            let radical = measure::<Renderer>(&MathBox::Radical(inner.clone()), size, font);
+            Measured {
+                width: index_overhang(&index) + radical.width,
+                ascent: f32::max(radical.ascent, index_raise(&radical) + index.ascent),
+                descent: radical.descent,
+                content: MeasuredContent::Root {
+                    index: Box::new(index),
+                    radical: Box::new(radical),
+                },
+            }
+        }
+        MathBox::Brackets(inner) => {
+            let inner = measure::<Renderer>(inner, size, font);
+            let open = measure_stretched::<Renderer>("(", inner.height(), size, font);
+            let close = measure_stretched::<Renderer>(")", inner.height(), size, font);
+            Measured {
+                width: open.width + inner.width + close.width,
+                ascent: f32::max(inner.ascent, open.ascent),
+                descent: f32::max(inner.descent, open.descent),
+                content: MeasuredContent::Brackets {
+                    open: Box::new(open),
+                    inner: Box::new(inner),
+                    close: Box::new(close),
+                },
+            }
+        }
+        MathBox::Absolute(inner) => {
+            let inner = measure::<Renderer>(inner, size, font);
+            Measured {
+                width: inner.width + gap * 2.0 + line_thickness(size) * 2.0,
+                ascent: inner.ascent + gap * 0.25,
+                descent: inner.descent + gap * 0.25,
+                content: MeasuredContent::Absolute(Box::new(inner)),
+            }
+        }
+    }
+}
+
+/// How far the index of a root sticks out to the left of the radical sign
+fn index_overhang(index: &Measured) -> f32 {
+    index.width * 0.6
+}
+
+/// How far above the centre line the middle of a root's index goes
+fn index_raise(radical: &Measured) -> f32 {
+    radical.ascent * 0.5
+}
+
+/// How far above the centre line the middle of an exponent goes
+fn exponent_raise(base: &Measured) -> f32 {
+    base.ascent * 0.8
+}
+
+fn text_of<Content>(content: Content, bounds: Size, size: f32, font: Font) -> Text<Content, Font> {
+    Text {
+        content,
+        bounds,
+        size: Pixels(size),
+        line_height: LineHeight::default(),
+        font,
+        horizontal_alignment: alignment::Horizontal::Left,
+        vertical_alignment: alignment::Vertical::Top,
+        // the maths symbols may need font fallback
+        shaping: Shaping::Advanced,
+        wrapping: Wrapping::None,
+    }
+}
+
+fn measure_text<Renderer>(content: &str, size: f32, font: Font) -> Measured
+where
+    Renderer: text::Renderer<Font = Font>,
+{
+    let bounds =
+        Renderer::Paragraph::with_text(text_of(content, Size::INFINITY, size, font)).min_bounds();
+    Measured {
+        width: bounds.width,
+        ascent: bounds.height / 2.0,
+        descent: bounds.height / 2.0,
+        content: MeasuredContent::Text {
+            content: content.to_string(),
+            size,
+        },
+    }
+}
+
+/// Measures a symbol like a bracket, making the font big enough that it covers `height`
+fn measure_stretched<Renderer>(symbol: &str, height: f32, size: f32, font: Font) -> Measured
+where
+    Renderer: text::Renderer<Font = Font>,
+{
+    let line_height = LineHeight::default().to_absolute(Pixels(1.0)).0;
+    measure_text::<Renderer>(symbol, f32::max(size, height / line_height), font)
+}
+
+/// Draws the measured box with its left edge at `position.x` and centre line at `position.y`
+fn draw_measured<Renderer>(
+    renderer: &mut Renderer,
+    measured: &Measured,
+    position: Point,
+    color: iced::Color,
+    viewport: &Rectangle,
+) where
+    Renderer: text::Renderer<Font = Font>,
+{
+    let Point { x, y } = position;
+    match &measured.content {
+        MeasuredContent::Text { content, size } => {
+            let bounds = Size::new(measured.width, measured.height());
+            let font = renderer.default_font();
+            renderer.fill_text(
+                text_of(content.clone(), bounds, *size, font),
+                Point::new(x, y - measured.ascent),
+                color,
+                *viewport,
+            );
+        }
+        MeasuredContent::Row(children) => {
+            let spacing = if children.len() > 1 {
+                (measured.width - children.iter().map(|child| child.width).sum::<f32>())
+                    / (children.len() - 1) as f32
+            } else {
+                0.0
+            };
+            let mut child_x = x;
+            for child in children {
+                draw_measured(renderer, child, Point::new(child_x, y), color, viewport);
+                child_x += child.width + spacing;
+            }
+        }
+        MeasuredContent::Fraction(numerator, denominator) => {
+            let bar_gap = (measured.ascent - numerator.height()).max(0.0);
+            draw_measured(
+                renderer,
+                numerator,
+                Point::new(
+                    x + (measured.width - numerator.width) / 2.0,
+                    y - bar_gap - numerator.descent,
+                ),
+                color,
+                viewport,
+            );
+            draw_measured(
+                renderer,
+                denominator,
+                Point::new(
+                    x + (measured.width - denominator.width) / 2.0,
+                    y + bar_gap + denominator.ascent,
+                ),
+                color,
+                viewport,
+            );
+            let thickness = line_thickness(numerator.height());
+            fill_line(
+                renderer,
+                Rectangle::new(
+                    Point::new(x + bar_gap, y - thickness / 2.0),
+                    Size::new(measured.width - bar_gap * 2.0, thickness),
+                ),
+                color,
+            );
+        }
+        MeasuredContent::Power(base, exponent) => {
+            draw_measured(renderer, base, position, color, viewport);
+            draw_measured(
+                renderer,
+                exponent,
+                Point::new(x + base.width, y - exponent_raise(base)),
+                color,
+                viewport,
+            );
+        }
+        MeasuredContent::Radical { sign, inner } => {
+            draw_measured(renderer, sign, position, color, viewport);
+            draw_measured(
+                renderer,
+                inner,
+                Point::new(x + sign.width, y),
+                color,
+                viewport,
+            );
+            // the bar over the top, joined on to the top of the sign
+            let thickness = line_thickness(sign.height());
+            let top = y - sign.ascent + sign.height() * 0.12;
+            fill_line(
+                renderer,
+                Rectangle::new(
+                    Point::new(x + sign.width * 0.85, top),
+                    Size::new(measured.width - sign.width * 0.85, thickness),
+                ),
+                color,
+            );
+        }
+        MeasuredContent::Root { index, radical } => {
+            draw_measured(
+                renderer,
+                index,
+                Point::new(x, y - index_raise(radical)),
+                color,
+                viewport,
+            );
+            draw_measured(
+                renderer,
+                radical,
+                Point::new(x + index_overhang(index), y),
+                color,
+                viewport,
+            );
+        }
+        MeasuredContent::Brackets { open, inner, close } => {
+            draw_measured(renderer, open, position, color, viewport);
+            draw_measured(
+                renderer,
+                inner,
+                Point::new(x + open.width, y),
+                color,
+                viewport,
+            );
+            draw_measured(
+                renderer,
+                close,
+                Point::new(x + open.width + inner.width, y),
+                color,
+                viewport,
+            );
+        }
+        MeasuredContent::Absolute(inner) => {
+            let thickness = line_thickness(inner.height());
+            let bar_size = Size::new(thickness, measured.height());
+            let top = y - measured.ascent;
+            fill_line(
+                renderer,
+                Rectangle::new(Point::new(x, top), bar_size),
+                color,
+            );
+            draw_measured(
+                renderer,
+                inner,
+                Point::new(x + (measured.width - inner.width) / 2.0, y),
+                color,
+                viewport,
+            );
+            fill_line(
+                renderer,
+                Rectangle::new(Point::new(x + measured.width - thickness, top), bar_size),
+                color,
+            );
+        }
+    }
+}
+
+fn fill_line<Renderer>(renderer: &mut Renderer, bounds: Rectangle, color: iced::Color)
+where
+    Renderer: text::Renderer<Font = Font>,
+{
+    renderer.fill_quad(
+        Quad {
+            bounds,
+            ..Default::default()
+        },
+        color,
+    );
+}
+
+impl<Message, Theme, Renderer> Widget<Message, Theme, Renderer> for MathView
+where
+    Renderer: text::Renderer<Font = Font>,
+{
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Shrink, Length::Shrink)
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let measured = measure::<Renderer>(&self.math_box, self.size, renderer.default_font());
+        layout::Node::new(limits.resolve(
+            Length::Shrink,
+            Length::Shrink,
+            Size::new(measured.width, measured.height()),
+        ))
+    }
+
+    fn draw(
+        &self,
+        _tree: &Tree,
+        renderer: &mut Renderer,
+        _theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let measured = measure::<Renderer>(&self.math_box, self.size, renderer.default_font());
+        let bounds = layout.bounds();
+        draw_measured(
+            renderer,
+            &measured,
+            Point::new(bounds.x, bounds.y + measured.ascent),
+            style.text_color,
+            viewport,
+        );
+    }
+}
+
+impl<Message, Theme, Renderer> From<MathView> for Element<'_, Message, Theme, Renderer>
+where
+    Renderer: text::Renderer<Font = Font>,
+{
+    fn from(math_view: MathView) -> Self {
+        Element::new(math_view)
+    }
+}
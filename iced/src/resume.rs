@@ -0,0 +1,202 @@
+// the session being played, saved after every answer so closing the app partway through
+// doesn't lose it
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    EquationModelState, EquationProgress, QuestionRecord, SessionRng, SessionSettings,
+    adaptive::AdaptiveDifficulty,
+    equation::saved::{LoadError, SCHEMA_VERSION, SavedQuestion, check_version},
+    storage,
+};
+
+/// What the unfinished session is saved as
+const SAVED_SESSION_NAME: &str = "session";
+
+/// An answered question with the question kept as its tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedRecord {
+    question: SavedQuestion,
+    correct_answer: String,
+    submitted_text: String,
+    correct: bool,
+    time_taken: Duration,
+}
+
+/// Everything needed to carry on with an unfinished session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedSession {
+    version: u32,
+    settings: SessionSettings,
+    seed: u64,
+    /// Saved so the rest of the questions are the same as if it was never stopped
+    rng: SessionRng,
+    /// How long the session had gone on for
+    time_taken: Duration,
+    current_question: SavedQuestion,
+    number_equations: Option<u32>,
+    records: Vec<SavedRecord>,
+    adaptive_difficulty: AdaptiveDifficulty,
+    queued_questions: Vec<SavedQuestion>,
+}
+
+impl SavedSession {
+    /// Saves the session as it is at `time_taken` on the timer, `None` if any of its questions
+    /// can't be saved
+    pub fn new(equation_state: &EquationModelState, time_taken: Duration) -> Option<Self> {
+        let records = equation_state
+            .equation_progress
+            .question_records
+            .iter()
+            .map(|record| {
+                Some(SavedRecord {
+                    question: record.equation.to_saved()?,
+                    correct_answer: record.correct_answer.clone(),
+                    submitted_text: record.submitted_text.clone(),
+                    correct: record.correct,
+                    time_taken: record.time_taken,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            version: SCHEMA_VERSION,
+            settings: equation_state.settings,
+            seed: equation_state.seed,
+            rng: (*equation_state.rng).clone(),
+            time_taken: equation_state.elapsed(time_taken),
+            current_question: equation_state.current_equation.to_saved()?,
+            number_equations: equation_state.equation_progress.number_equations,
+            records,
+            adaptive_difficulty: equation_state.adaptive_difficulty.clone(),
+            queued_questions: equation_state.queued_questions.clone(),
+        })
+    }
+
+    /// Carries on with the session from `now` on the timer
+    pub fn into_state(self, now: Duration) -> EquationModelState {
+        let mut equation_progress = EquationProgress::new(self.number_equations);
+        for record in self.records {
+            equation_progress.add_equation(QuestionRecord {
+                equation: record.question.into_question(),
+                correct_answer: record.correct_answer,
+                submitted_text: record.submitted_text,
+                correct: record.correct,
+                time_taken: record.time_taken,
+            });
+        }
+        EquationModelState {
+            current_equation: self.current_question.into_question(),
+            settings: self.settings,
+            start_time: now,
+            time_before_resume: self.time_taken,
+            question_start_time: now,
+            seed: self.seed,
+            rng: Box::new(self.rng),
+            current_answer_text: String::new(),
+            answer_error: None,
+            equation_progress,
+            adaptive_difficulty: self.adaptive_difficulty,
+            queued_questions: self.queued_questions,
+        }
+    }
+
+    /// The unfinished session if there is one, ignoring it if it can't be read
+    pub fn load() -> Option<Self> {
+        Self::from_json(&storage::load(SAVED_SESSION_NAME)?).ok()
+    }
+
+    /// Reads a saved session, checking every question in it can be worked out
+    fn from_json(text: &str) -> Result<Self, LoadError> {
+        check_version(text)?;
+        let session: Self =
+            serde_json::from_str(text).map_err(|err| LoadError::Json(err.to_string()))?;
+        std::iter::once(&session.current_question)
+            .chain(session.records.iter().map(|record| &record.question))
+            .chain(&session.queued_questions)
+            .try_for_each(SavedQuestion::check)?;
+        Ok(session)
+    }
+
+    pub fn save(&self) {
+        storage::save_json(SAVED_SESSION_NAME, self);
+    }
+
+    /// Forgets the saved session once it's finished
+    pub fn clear() {
+        storage::remove(SAVED_SESSION_NAME);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+    use crate::{new_equation_model_state, next_equation};
+
+    #[test]
+    fn session_rng_matches_std_rng() {
+        // seeds written down before sessions could be saved should give the same questions
+        let mut session_rng = SessionRng::seed_from_u64(22);
+        let mut std_rng = StdRng::seed_from_u64(22);
+        for _ in 0..10 {
+            assert_eq!(session_rng.random::<u64>(), std_rng.random::<u64>());
+        }
+    }
+
+    #[test]
+    fn resumes_where_it_left_off() {
+        let mut equation_state = new_equation_model_state(
+            SessionSettings::default(),
+            22,
+            Duration::from_secs(5),
+            Vec::new(),
+        );
+        let saved = SavedSession::new(&equation_state, Duration::from_secs(35)).unwrap();
+        let text = serde_json::to_string(&saved).unwrap();
+        let mut resumed = SavedSession::from_json(&text)
+            .unwrap()
+            .into_state(Duration::ZERO);
+
+        assert_eq!(
+            resumed.current_equation.to_string(),
+            equation_state.current_equation.to_string()
+        );
+        assert_eq!(
+            resumed.elapsed(Duration::from_secs(10)),
+            Duration::from_secs(40)
+        );
+        for _ in 0..10 {
+            let expected = next_equation(
+                &equation_state.settings,
+                &equation_state.adaptive_difficulty,
+                &mut equation_state.rng,
+                &mut equation_state.queued_questions,
+            );
+            let question = next_equation(
+                &resumed.settings,
+                &resumed.adaptive_difficulty,
+                &mut resumed.rng,
+                &mut resumed.queued_questions,
+            );
+            assert_eq!(question.to_string(), expected.to_string());
+        }
+    }
+
+    #[test]
+    fn ignores_sessions_with_broken_questions() {
+        let equation_state =
+            new_equation_model_state(SessionSettings::default(), 22, Duration::ZERO, Vec::new());
+        let mut saved = SavedSession::new(&equation_state, Duration::ZERO).unwrap();
+        saved.queued_questions = vec![
+            serde_json::from_str(r#"{"type": "modulo", "dividend": 4, "divisor": 0}"#).unwrap(),
+        ];
+        let text = serde_json::to_string(&saved).unwrap();
+        assert!(matches!(
+            SavedSession::from_json(&text),
+            Err(LoadError::InvalidQuestion)
+        ));
+    }
+}
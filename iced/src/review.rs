@@ -0,0 +1,179 @@
+// spaced repetition of missed questions, using Leitner boxes so a question waits longer before
+// coming back each time it's answered right in a row
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    equation::saved::{LoadError, SCHEMA_VERSION, SavedQuestion, check_version},
+    storage,
+};
+
+/// What the review deck is saved as
+const REVIEW_DECK_NAME: &str = "review";
+
+/// The days to wait before a question in each box is due again. Missed questions go in the
+/// first box so they're due straight away
+const BOX_INTERVAL_DAYS: [u64; 5] = [0, 1, 3, 7, 14];
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A missed question and when it should be asked again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewItem {
+    pub question: SavedQuestion,
+    /// The Leitner box the question is in, an index into [`BOX_INTERVAL_DAYS`]
+    pub leitner_box: usize,
+    /// Seconds since the unix epoch when the question is next due
+    pub due: u64,
+}
+
+impl ReviewItem {
+    fn schedule(&mut self, leitner_box: usize, now: u64) {
+        self.leitner_box = leitner_box;
+        self.due = now + BOX_INTERVAL_DAYS[leitner_box] * SECONDS_PER_DAY;
+    }
+}
+
+/// Every question which has been missed and not learned yet
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReviewDeck {
+    version: u32,
+    items: Vec<ReviewItem>,
+}
+
+impl Default for ReviewDeck {
+    fn default() -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            items: Vec::new(),
+        }
+    }
+}
+
+impl ReviewDeck {
+    /// Loads the saved deck, starting an empty one if there isn't one or it can't be read
+    pub fn load() -> Self {
+        storage::load_with(REVIEW_DECK_NAME, Self::from_json).unwrap_or_default()
+    }
+
+    /// Reads a saved deck, dropping questions which can't be worked out so they don't panic
+    /// when asked
+    fn from_json(text: &str) -> Result<Self, LoadError> {
+        check_version(text)?;
+        let mut deck: Self =
+            serde_json::from_str(text).map_err(|err| LoadError::Json(err.to_string()))?;
+        deck.items.retain(|item| item.question.check().is_ok());
+        Ok(deck)
+    }
+
+    pub fn save(&self) {
+        storage::save_json(REVIEW_DECK_NAME, self);
+    }
+
+    /// The questions due at `now`, the longest overdue first
+    pub fn due_questions(&self, now: u64) -> Vec<SavedQuestion> {
+        let mut due: Vec<&ReviewItem> = self.items.iter().filter(|item| item.due <= now).collect();
+        due.sort_by_key(|item| item.due);
+        due.into_iter().map(|item| item.question.clone()).collect()
+    }
+
+    pub fn due_count(&self, now: u64) -> usize {
+        self.items.iter().filter(|item| item.due <= now).count()
+    }
+
+    /// Updates the deck after a question's been answered, returning whether anything changed.
+    /// Missed questions go back to the first box, due questions answered right move up a box
+    /// and ones answered right from the last box are learned and taken out
+    pub fn record_answer(&mut self, question: SavedQuestion, correct: bool, now: u64) -> bool {
+        let position = self.items.iter().position(|item| item.question == question);
+        match (position, correct) {
+            (None, true) => false,
+            (None, false) => {
+                self.items.push(ReviewItem {
+                    question,
+                    leitner_box: 0,
+                    due: now + BOX_INTERVAL_DAYS[0] * SECONDS_PER_DAY,
+                });
+                true
+            }
+            (Some(index), false) => {
+                self.items[index].schedule(0, now);
+                true
+            }
+            (Some(index), true) => {
+                let item = &mut self.items[index];
+                // answering right before it's due doesn't count as a review
+                if item.due > now {
+                    return false;
+                }
+                if item.leitner_box + 1 == BOX_INTERVAL_DAYS.len() {
+                    self.items.remove(index);
+                } else {
+                    item.schedule(item.leitner_box + 1, now);
+                }
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn question(dividend: i64) -> SavedQuestion {
+        serde_json::from_str(&format!(
+            r#"{{"type": "modulo", "dividend": {}, "divisor": 5}}"#,
+            dividend
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn moves_questions_through_boxes() {
+        let mut deck = ReviewDeck::default();
+        assert!(!deck.record_answer(question(17), true, 0));
+        assert!(deck.record_answer(question(17), false, 0));
+        assert!(deck.record_answer(question(18), false, 10));
+        assert_eq!(deck.due_questions(10), vec![question(17), question(18)]);
+
+        // right answers space the reviews out until the question is learned
+        let mut now = 10;
+        for interval in &BOX_INTERVAL_DAYS[1..] {
+            assert!(deck.record_answer(question(17), true, now));
+            now += interval * SECONDS_PER_DAY;
+            assert_eq!(deck.due_count(now - 1), 1);
+        }
+        assert!(deck.record_answer(question(17), true, now));
+        assert_eq!(deck.due_questions(now), vec![question(18)]);
+
+        // a miss sends it back to the start
+        assert!(deck.record_answer(question(18), true, now));
+        assert!(deck.record_answer(question(18), false, now));
+        assert_eq!(deck.due_count(now), 1);
+        let question = deck.due_questions(now).remove(0).into_question();
+        assert_eq!(question.answer_text(), "3");
+    }
+
+    #[test]
+    fn reads_saved_decks() {
+        let mut deck = ReviewDeck::default();
+        deck.record_answer(question(17), false, 0);
+        deck.record_answer(question(18), false, 0);
+        deck.items[1].question =
+            serde_json::from_str(r#"{"type": "modulo", "dividend": 18, "divisor": 0}"#).unwrap();
+        let text = serde_json::to_string(&deck).unwrap();
+        assert!(text.starts_with(r#"{"version":1,"#));
+        let loaded = ReviewDeck::from_json(&text).unwrap();
+        assert_eq!(loaded.due_questions(0), vec![question(17)]);
+
+        assert_eq!(
+            ReviewDeck::from_json(r#"{"version": 2, "items": []}"#).unwrap_err(),
+            LoadError::UnsupportedVersion(2)
+        );
+        assert!(matches!(
+            ReviewDeck::from_json(r#"{"items": []}"#),
+            Err(LoadError::Json(_))
+        ));
+    }
+}
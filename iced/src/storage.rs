@@ -0,0 +1,103 @@
+// saving text that should survive restarting the app, files natively and local storage on the web
+
+use std::fmt::Display;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::{fs, path::PathBuf};
+
+    /// Overrides where everything is saved, so scripts and tests don't touch the player's data
+    const DATA_DIR_VARIABLE: &str = "NUMBER_PAIN_DATA_DIR";
+
+    fn file_path(name: &str) -> Option<PathBuf> {
+        let dir = match std::env::var_os(DATA_DIR_VARIABLE) {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::data_dir()?.join("number-pain"),
+        };
+        Some(dir.join(format!("{}.json", name)))
+    }
+
+    pub fn load(name: &str) -> Option<String> {
+        fs::read_to_string(file_path(name)?).ok()
+    }
+
+    pub fn save(name: &str, text: &str) -> Result<(), String> {
+        let path = file_path(name).ok_or("no data directory to save to")?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        fs::write(path, text).map_err(|err| err.to_string())
+    }
+
+    pub fn remove(name: &str) {
+        if let Some(path) = file_path(name) {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    fn key(name: &str) -> String {
+        format!("number-pain-{}", name)
+    }
+
+    pub fn load(name: &str) -> Option<String> {
+        local_storage()?.get_item(&key(name)).ok()?
+    }
+
+    pub fn save(name: &str, text: &str) -> Result<(), String> {
+        local_storage()
+            .ok_or("no local storage to save to")?
+            .set_item(&key(name), text)
+            .map_err(|_| "couldn't write to local storage".to_string())
+    }
+
+    pub fn remove(name: &str) {
+        if let Some(storage) = local_storage() {
+            let _ = storage.remove_item(&key(name));
+        }
+    }
+}
+
+pub use platform::{load, remove, save};
+
+/// Loads what was saved as `name` and reads it as json. Text which can't be read is copied to
+/// `{name}-unreadable` first, so saving over it afterwards doesn't lose it
+pub fn load_json<T: DeserializeOwned>(name: &str) -> Option<T> {
+    load_with(name, |text| serde_json::from_str(text))
+}
+
+/// Loads what was saved as `name` and reads it with `read`, keeping text which can't be read
+/// the same way as [`load_json`]
+pub fn load_with<T, E: Display>(name: &str, read: impl FnOnce(&str) -> Result<T, E>) -> Option<T> {
+    let text = load(name)?;
+    match read(&text) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            let backup = format!("{}-unreadable", name);
+            eprintln!("Couldn't read {}, keeping it as {}: {}", name, backup, err);
+            if let Err(err) = save(&backup, &text) {
+                eprintln!("Couldn't keep {}: {}", name, err);
+            }
+            None
+        }
+    }
+}
+
+/// Saves `value` as json under `name`, only printing an error if it can't be saved since
+/// there's nothing else to do about it
+pub fn save_json<T: Serialize>(name: &str, value: &T) {
+    let saved = serde_json::to_string(value)
+        .map_err(|err| err.to_string())
+        .and_then(|text| save(name, &text));
+    if let Err(err) = saved {
+        eprintln!("Couldn't save {}: {}", name, err);
+    }
+}
@@ -0,0 +1,483 @@
+// printable worksheets of generated questions with an answer key, as html and pdf
+
+use rand::SeedableRng;
+
+use crate::{
+    SessionRng, SessionSettings,
+    adaptive::AdaptiveDifficulty,
+    equation::{Question, answer::SUPERSCRIPT_DIGITS},
+    new_equation,
+};
+
+/// The width and height of an A4 page in points
+const PAGE_SIZE: (f32, f32) = (595.0, 842.0);
+const PAGE_MARGIN: f32 = 50.0;
+const TITLE_SIZE: f32 = 18.0;
+const QUESTION_SIZE: f32 = 12.0;
+/// The space each question gets, enough to write the working out under it
+const QUESTION_HEIGHT: f32 = 60.0;
+const ANSWER_HEIGHT: f32 = 20.0;
+/// The distance between the lines of an item too wide for its column
+const LINE_HEIGHT: f32 = 15.0;
+/// Any more and the columns are too narrow for most questions
+pub const MAX_COLUMNS: usize = 4;
+/// The space left between one column and the next
+const COLUMN_GAP: f32 = 12.0;
+
+/// Questions laid out in columns, with their answers on a separate page
+#[derive(Debug)]
+pub struct Worksheet {
+    pub title: String,
+    pub seed: u64,
+    pub columns: usize,
+    pub questions: Vec<Box<dyn Question>>,
+}
+
+impl Worksheet {
+    /// Generates `number_questions` questions the same way a session with `settings` would
+    pub fn new(
+        settings: &SessionSettings,
+        number_questions: usize,
+        columns: usize,
+        seed: u64,
+    ) -> Self {
+        let mut rng = SessionRng::seed_from_u64(seed);
+        // adaptive questions stay at the starting level since nothing gets answered
+        let adaptive_difficulty = AdaptiveDifficulty::new(0.5);
+        Self {
+            title: format!("{} worksheet", settings.equation_difficulty),
+            seed,
+            columns: columns.clamp(1, MAX_COLUMNS),
+            questions: (0..number_questions)
+                .map(|_| new_equation(settings, &adaptive_difficulty, &mut rng))
+                .collect(),
+        }
+    }
+
+    /// A page which can be opened and printed from a browser, with the questions as MathML
+    pub fn to_html(&self) -> String {
+        let questions: String = self
+            .questions
+            .iter()
+            .map(|question| format!("<li>{}</li>\n", question.to_mathml()))
+            .collect();
+        let answers: String = self
+            .questions
+            .iter()
+            .map(|question| format!("<li>{}</li>\n", escape_html(&question.answer_text())))
+            .collect();
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+ol {{ columns: {columns}; column-gap: 3em; }}
+.questions li {{ break-inside: avoid; margin-bottom: 3em; }}
+.questions math {{ text-align: left; }}
+.answers {{ break-before: page; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>Name: ____________________ Seed {seed}</p>
+<ol class="questions">
+{questions}</ol>
+<section class="answers">
+<h2>Answers</h2>
+<ol>
+{answers}</ol>
+</section>
+</body>
+</html>
+"#,
+            title = escape_html(&self.title),
+            columns = self.columns,
+            seed = self.seed,
+            questions = questions,
+            answers = answers,
+        )
+    }
+
+    /// An A4 pdf with the questions on the first pages and the answer key after
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let questions: Vec<String> = self
+            .questions
+            .iter()
+            .map(|question| question.to_string())
+            .collect();
+        let answers: Vec<String> = self
+            .questions
+            .iter()
+            .map(|question| question.answer_text())
+            .collect();
+        let mut pages = self.pdf_pages(&self.title, &questions, QUESTION_HEIGHT);
+        pages.extend(self.pdf_pages("Answers", &answers, ANSWER_HEIGHT));
+        write_pdf(&pages)
+    }
+
+    /// The content streams for numbered items in columns, starting new pages as they fill up.
+    /// Items too wide for their column are wrapped onto more lines
+    fn pdf_pages(&self, heading: &str, items: &[String], item_height: f32) -> Vec<String> {
+        let (width, height) = PAGE_SIZE;
+        let top = height - PAGE_MARGIN - TITLE_SIZE * 2.0;
+        let column_width = (width - 2.0 * PAGE_MARGIN) / self.columns as f32;
+
+        let heading = format!("{}    Seed {}", heading, self.seed);
+        let mut pages = vec![pdf_text_at(
+            PAGE_MARGIN,
+            height - PAGE_MARGIN,
+            TITLE_SIZE,
+            &heading,
+        )];
+        let (mut column, mut y) = (0, top);
+        for (index, item) in items.iter().enumerate() {
+            let number = format!("{}.  ", index + 1);
+            let indent = text_width(&number, QUESTION_SIZE);
+            let lines = wrap(item, column_width - COLUMN_GAP - indent, QUESTION_SIZE);
+            let needed = item_height + (lines.len() - 1) as f32 * LINE_HEIGHT;
+
+            // down each column before moving across, like the html
+            if y - needed < PAGE_MARGIN && y < top {
+                column += 1;
+                y = top;
+                if column == self.columns {
+                    pages.push(String::new());
+                    column = 0;
+                }
+            }
+            let x = PAGE_MARGIN + column as f32 * column_width;
+            let content = pages.last_mut().expect("there's always a first page");
+            *content += &pdf_text_at(x, y, QUESTION_SIZE, &number);
+            for (line_number, line) in lines.iter().enumerate() {
+                let line_y = y - line_number as f32 * LINE_HEIGHT;
+                *content += &pdf_text_at(x + indent, line_y, QUESTION_SIZE, line);
+            }
+            y -= needed;
+        }
+        pages
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A line of text drawn with its baseline starting at `x`, `y`
+fn pdf_text_at(x: f32, y: f32, size: f32, text: &str) -> String {
+    format!(
+        "BT /F1 {} Tf {} {} Td ({}) Tj ET\n",
+        size,
+        x,
+        y,
+        pdf_string(text)
+    )
+}
+
+/// Splits text into lines no wider than `max_width`, between words where it can
+fn wrap(text: &str, max_width: f32, size: f32) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split(' ') {
+        let joined = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if line.is_empty() || text_width(&joined, size) <= max_width {
+            line = joined;
+            continue;
+        }
+        lines.push(std::mem::replace(&mut line, word.to_string()));
+    }
+    lines.push(line);
+
+    // a single word can still be too wide, e.g. a long number, so those are split anywhere
+    lines
+        .into_iter()
+        .flat_map(|line| {
+            let mut pieces = vec![String::new()];
+            for character in line.chars() {
+                let piece = pieces.last_mut().expect("pieces is never empty");
+                if !piece.is_empty()
+                    && text_width(&format!("{}{}", piece, character), size) > max_width
+                {
+                    pieces.push(character.to_string());
+                } else {
+                    piece.push(character);
+                }
+            }
+            pieces
+        })
+        .collect()
+}
+
+/// How wide the text is in Helvetica at `size` points
+fn text_width(text: &str, size: f32) -> f32 {
+    let units: u32 = win_ansi(text)
+        .into_iter()
+        .map(|byte| u32::from(helvetica_width(byte)))
+        .sum();
+    units as f32 * size / 1000.0
+}
+
+/// The width of a WinAnsi character in thousandths of the font size, from Helvetica's AFM
+fn helvetica_width(byte: u8) -> u16 {
+    match byte {
+        b' ' | b'!' | b',' | b'.' | b'/' | b':' | b';' | b'I' | b'[' | b'\\' | b']' | b'f'
+        | b't' => 278,
+        b'"' => 355,
+        b'%' => 889,
+        b'&' | b'A' | b'B' | b'E' | b'K' | b'P' | b'S' | b'V' | b'X' | b'Y' => 667,
+        b'\'' => 191,
+        b'(' | b')' | b'-' | b'`' | b'r' | 0xb2 | 0xb3 => 333,
+        b'*' => 389,
+        b'+' | b'<' | b'=' | b'>' | b'~' | 0xd7 | 0xf7 => 584,
+        b'@' => 1015,
+        b'C' | b'D' | b'H' | b'N' | b'R' | b'U' | b'w' => 722,
+        b'F' | b'T' | b'Z' => 611,
+        b'G' | b'O' | b'Q' => 778,
+        b'J' | b'c' | b'k' | b's' | b'v' | b'x' | b'y' | b'z' => 500,
+        b'M' | b'm' => 833,
+        b'W' => 944,
+        b'^' => 469,
+        b'i' | b'j' | b'l' => 222,
+        b'{' | b'}' => 334,
+        b'|' => 260,
+        // digits, the rest of the lowercase letters and anything else
+        _ => 556,
+    }
+}
+
+/// Writes text for the built in Helvetica font in a pdf string, escaping the brackets and
+/// any characters outside ascii
+fn pdf_string(text: &str) -> String {
+    let mut string = String::new();
+    for byte in win_ansi(text) {
+        match byte {
+            b'(' | b')' | b'\\' => {
+                string.push('\\');
+                string.push(char::from(byte));
+            }
+            byte if byte.is_ascii() => string.push(char::from(byte)),
+            byte => string += &format!("\\{:o}", byte),
+        }
+    }
+    string
+}
+
+/// Converts text to the WinAnsi characters the built in Helvetica font has. Symbols it doesn't
+/// have are spelled out, e.g. `√16` as `sqrt 16` and `2⁵` as `2^5`
+fn win_ansi(text: &str) -> Vec<u8> {
+    let mut bytes = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(character) = chars.next() {
+        match character {
+            '×' => bytes.push(0xd7),
+            '÷' => bytes.push(0xf7),
+            '−' => bytes.push(b'-'),
+            '√' => bytes.extend(b"sqrt "),
+            '∛' => bytes.extend(b"cbrt "),
+            '²' if !chars
+                .peek()
+                .is_some_and(|next| SUPERSCRIPT_DIGITS.contains(next)) =>
+            {
+                bytes.push(0xb2)
+            }
+            '³' if !chars
+                .peek()
+                .is_some_and(|next| SUPERSCRIPT_DIGITS.contains(next)) =>
+            {
+                bytes.push(0xb3)
+            }
+            c if SUPERSCRIPT_DIGITS.contains(&c) => {
+                bytes.push(b'^');
+                let mut digit = Some(c);
+                while let Some(c) = digit {
+                    let value = SUPERSCRIPT_DIGITS.iter().position(|&d| d == c).unwrap_or(0);
+                    bytes.push(b'0' + value as u8);
+                    digit = chars.next_if(|next| SUPERSCRIPT_DIGITS.contains(next));
+                }
+            }
+            c if c.is_ascii() => bytes.push(c as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes
+}
+
+/// A pdf with a page for each content stream, all using Helvetica as `/F1`
+fn write_pdf(pages: &[String]) -> Vec<u8> {
+    // the catalog, page tree and font come first, then each page and its content
+    let page_ids: Vec<usize> = (0..pages.len()).map(|page| 4 + page * 2).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (content, id) in pages.iter().zip(&page_ids) {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            PAGE_SIZE.0,
+            PAGE_SIZE.1,
+            id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}endstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut pdf = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf += &format!("{} 0 obj\n{}\nendobj\n", index + 1, object);
+    }
+    let xref_offset = pdf.len();
+    pdf += &format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        pdf += &format!("{:010} 00000 n \n", offset);
+    }
+    pdf += &format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    );
+    pdf.into_bytes()
+}
+
+/// Saves the worksheet as `{name}.html` and `{name}.pdf` in the documents folder, returning
+/// where they went
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(worksheet: &Worksheet, name: &str) -> Result<String, String> {
+    let dir = dirs::document_dir()
+        .or_else(dirs::home_dir)
+        .ok_or("no documents folder to save to")?;
+    save_to(worksheet, &dir.join(name))
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(_worksheet: &Worksheet, _name: &str) -> Result<String, String> {
+    Err("Worksheets can only be saved from the desktop app".to_string())
+}
+
+/// Saves the worksheet next to `path` with `.html` and `.pdf` extensions
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_to(worksheet: &Worksheet, path: &std::path::Path) -> Result<String, String> {
+    let html_path = path.with_extension("html");
+    let pdf_path = path.with_extension("pdf");
+    std::fs::write(&html_path, worksheet.to_html()).map_err(|err| err.to_string())?;
+    std::fs::write(&pdf_path, worksheet.to_pdf()).map_err(|err| err.to_string())?;
+    Ok(format!(
+        "Saved {} and {}",
+        html_path.display(),
+        pdf_path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EquationDifficulty;
+
+    fn worksheet(number_questions: usize) -> Worksheet {
+        let settings = SessionSettings {
+            equation_difficulty: EquationDifficulty::Medium,
+            ..Default::default()
+        };
+        Worksheet::new(&settings, number_questions, 2, 24)
+    }
+
+    #[test]
+    fn writes_questions_and_answers_to_html() {
+        let worksheet = worksheet(12);
+        let html = worksheet.to_html();
+        assert!(html.contains("<title>Medium worksheet</title>"));
+        assert!(html.contains("columns: 2;"));
+        assert_eq!(html.matches("<li><math").count(), 12);
+        let answers = &html[html.find("<h2>Answers</h2>").unwrap()..];
+        assert_eq!(answers.matches("<li>").count(), 12);
+        assert!(answers.contains(&format!(
+            "<li>{}</li>",
+            worksheet.questions[0].answer_text()
+        )));
+    }
+
+    #[test]
+    fn writes_pdf_pages_with_answer_key() {
+        let pdf = String::from_utf8(worksheet(40).to_pdf()).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        // 40 questions need two pages at 2 columns, the answers fit on one
+        assert!(pdf.contains("/Count 3"));
+        assert!(pdf.contains("(Answers    Seed 24) Tj"));
+
+        let xref = pdf[pdf.rfind("startxref\n").unwrap() + 10..]
+            .lines()
+            .next()
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        assert!(pdf[xref..].starts_with("xref"));
+        let first_object = pdf[xref..].lines().nth(3).unwrap()[..10]
+            .parse::<usize>()
+            .unwrap();
+        assert!(pdf[first_object..].starts_with("1 0 obj"));
+    }
+
+    #[test]
+    fn spells_out_missing_symbols() {
+        assert_eq!(pdf_string("(3 × 4)²"), "\\(3 \\327 4\\)\\262");
+        assert_eq!(
+            pdf_string("√16 + ∛27 ÷ 2⁴⁵"),
+            "sqrt 16 + cbrt 27 \\367 2^45"
+        );
+        assert_eq!(pdf_string("2²³"), "2^23");
+    }
+
+    #[test]
+    fn wraps_items_wider_than_their_column() {
+        assert_eq!(text_width("1.  ", 1000.0), 1390.0);
+        assert_eq!(text_width("√4 × 3²", 1000.0), 4530.0);
+        assert_eq!(wrap("(1 + 2) × 3 - 4", 35.0, 10.0), ["(1 + 2)", "× 3 - 4"]);
+        assert_eq!(wrap("1234567", 20.0, 10.0), ["123", "456", "7"]);
+        for line in wrap("√16 + ∛27 ÷ 2⁴⁵ - 1,000,000,000,000", 50.0, 12.0) {
+            assert!(text_width(&line, 12.0) <= 50.0, "{}", line);
+        }
+
+        let mut worksheet = worksheet(3);
+        worksheet.columns = MAX_COLUMNS;
+        let items = ["1 + 2".to_string(), "3 ".repeat(40), "4".to_string()];
+        let pages = worksheet.pdf_pages("Test", &items, ANSWER_HEIGHT);
+        let lines: Vec<_> = pages[0].lines().skip(1).collect();
+        // the second item is wrapped so the third starts lower down
+        assert!(lines.len() > 6, "{:?}", lines);
+        assert!(lines.last().unwrap().ends_with("(4) Tj ET"));
+        for line in &lines {
+            let text = &line[line.find('(').unwrap() + 1..line.rfind(')').unwrap()];
+            let x: f32 = line.split(' ').nth(4).unwrap().parse().unwrap();
+            let column_end = PAGE_MARGIN + (PAGE_SIZE.0 - 2.0 * PAGE_MARGIN) / 4.0;
+            assert!(
+                x + text_width(text, QUESTION_SIZE) <= column_end,
+                "{}",
+                line
+            );
+        }
+    }
+}
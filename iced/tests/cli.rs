@@ -0,0 +1,146 @@
+// runs the game from the command line the way a script would, answering questions over stdin
+
+use std::{
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+/// A data folder of its own for each test so nothing touches the player's history
+fn data_dir(test: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("number-pain-test-{}-{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Runs `iced` with `args`, typing `input` in, and returns what it printed
+fn run(data_dir: &PathBuf, args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_iced"))
+        .args(args)
+        .env("NUMBER_PAIN_DATA_DIR", data_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// The correct answers printed after each wrong one
+fn correct_answers(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once("Wrong, the answer is "))
+        .map(|(_, answer)| answer.to_string())
+        .collect()
+}
+
+#[test]
+fn replays_a_seed_with_the_right_answers() {
+    let data_dir = data_dir("replay");
+    let args = [
+        "play",
+        "--difficulty",
+        "complex",
+        "--questions",
+        "5",
+        "--seed",
+        "25",
+    ];
+
+    let output = run(&data_dir, &args, &"0\n".repeat(5));
+    let answers = correct_answers(&output);
+    assert_eq!(answers.len(), 5, "{}", output);
+    assert!(output.contains("Scored 0/5"), "{}", output);
+    assert!(output.ends_with("Seed 25\n"), "{}", output);
+
+    // the same seed asks the same questions
+    let output = run(&data_dir, &args, &(answers.join("\n") + "\n"));
+    assert_eq!(output.matches("Right").count(), 5, "{}", output);
+    assert!(output.contains("Scored 5/5"), "{}", output);
+
+    // both sessions were saved to the history
+    let history = std::fs::read_to_string(data_dir.join("history.json")).unwrap();
+    assert_eq!(history.matches("\"equations_completed\"").count(), 2);
+}
+
+#[test]
+fn asks_again_after_an_answer_it_cant_read() {
+    let data_dir = data_dir("typo");
+    let output = run(
+        &data_dir,
+        &[
+            "--tui",
+            "--difficulty",
+            "division-with-remainders",
+            "--seed",
+            "3",
+        ],
+        "oops\n4\n",
+    );
+    let lines: Vec<_> = output.lines().collect();
+    assert!(lines[0].starts_with("1/10  "), "{}", output);
+    assert!(
+        lines[0].ends_with("(Quotient and remainder, e.g. 3 r 2)"),
+        "{}",
+        output
+    );
+    assert_eq!(lines[1], "> Didn't expect 'oops' there");
+    assert!(lines[2].starts_with("1/10  "), "{}", output);
+    assert_eq!(lines[3], "> Write the answer with a remainder, e.g. 3 r 2");
+    // the summary goes on its own line once the input runs out
+    assert_eq!(lines[5], "> ");
+    // and there's no rate since it was over in under a second
+    assert_eq!(lines[6..], ["Scored 0/0 in 0:00", "Seed 3"]);
+
+    // nothing was answered so there's no session to save
+    assert!(!data_dir.join("history.json").exists());
+}
+
+#[test]
+fn saves_worksheets() {
+    let data_dir = data_dir("worksheet");
+    let path = data_dir.join("sheet");
+    let output = run(
+        &data_dir,
+        &[
+            "worksheet",
+            "--questions",
+            "8",
+            "--seed",
+            "1",
+            "--output",
+            path.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert!(output.starts_with("Saved "), "{}", output);
+    let html = std::fs::read_to_string(path.with_extension("html")).unwrap();
+    assert_eq!(html.matches("<li><math").count(), 8);
+    let pdf = std::fs::read(path.with_extension("pdf")).unwrap();
+    assert!(pdf.starts_with(b"%PDF-1.4"));
+}
+
+#[test]
+fn rejects_unknown_commands() {
+    let output = Command::new(env!("CARGO_BIN_EXE_iced"))
+        .arg("fly")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Unknown command"));
+}